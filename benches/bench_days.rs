@@ -1,25 +1,67 @@
 #![allow(clippy::zero_prefixed_literal)]
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use paste::paste;
 
-/// Get input for a single day
-macro_rules! get_day_input {
-    ($day_num:literal) => {
-        include_str!(concat!("../inputs/day", $day_num, ".txt"))
-    };
+/// Get input for a single day, `None` when it is missing or empty
+fn get_day_input(day: &str) -> Option<String> {
+    let path = format!("{}/inputs/day{day}.txt", env!("CARGO_MANIFEST_DIR"));
+    match std::fs::read_to_string(&path) {
+        Ok(input) if !input.trim().is_empty() => Some(input),
+        _ => {
+            eprintln!("Skipping day{day}: no input at {path}");
+            None
+        }
+    }
 }
 
-/// Define benchmarks for a single day with part1 and part2
+/// Define benchmarks for a single day: both parts as a whole, then parsing and solving apart
 macro_rules! benches_day {
     ($day_num:literal) => {
         paste! {
             use aoc24::[<day $day_num>]; // Replace `aoc24` with your crate name
 
             pub fn [<bench_day $day_num>](c: &mut Criterion) {
-                let mut group = c.benchmark_group(concat!("day", $day_num));
-                let input = get_day_input!($day_num);
-                group.bench_function(format!("day{}_part1", $day_num), |b| b.iter(|| [<day $day_num>]::part1(input)));
-                group.bench_function(format!("day{}_part2", $day_num), |b| b.iter(|| [<day $day_num>]::part2(input)));
+                let Some(input) = get_day_input(stringify!($day_num)) else {
+                    return;
+                };
+                let input = input.as_str();
+                if let Err(e) = [<day $day_num>]::parse(input) {
+                    eprintln!("Skipping day{}: {e}", stringify!($day_num));
+                    return;
+                }
+
+                let mut group = c.benchmark_group(concat!("day", stringify!($day_num)));
+                group.bench_function("part1", |b| b.iter(|| [<day $day_num>]::part1(input)));
+                group.bench_function("part2", |b| b.iter(|| [<day $day_num>]::part2(input)));
+                // Alternative implementations next to the part they replace
+                for variant in aoc24::variants().filter(|variant| variant.day == $day_num) {
+                    group.bench_function(variant.name, |b| b.iter(|| variant.solve(input)));
+                }
+                group.finish();
+
+                let mut group = c.benchmark_group("parse");
+                group.bench_function(concat!("day", stringify!($day_num)), |b| {
+                    b.iter(|| [<day $day_num>]::parse(input))
+                });
+                group.finish();
+
+                // Solvers consume the parsed input, which is rebuilt outside of the measurement
+                let mut group = c.benchmark_group("solve");
+                group.bench_function(concat!("day", stringify!($day_num), "_part1"), |b| {
+                    b.iter_batched(
+                        || [<day $day_num>]::parse(input).unwrap(),
+                        [<day $day_num>]::part1_solve,
+                        BatchSize::SmallInput,
+                    )
+                });
+                group.bench_function(concat!("day", stringify!($day_num), "_part2"), |b| {
+                    b.iter_batched(
+                        || [<day $day_num>]::parse(input).unwrap(),
+                        [<day $day_num>]::part2_solve,
+                        BatchSize::SmallInput,
+                    )
+                });
+                group.finish();
             }
         }
     };
@@ -39,5 +81,4 @@ macro_rules! benches {
     };
 }
 
-// benches!(01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 25); // Add more days here
-benches!(10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22); // Add more days here
+benches!(01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22); // Add more days here
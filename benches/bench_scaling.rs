@@ -0,0 +1,160 @@
+//! Selected days over generated inputs of increasing size, to compare the time per size with the
+//! expected complexity of the solvers
+use aoc24::{
+    day11, day12, day18, day20, day22,
+    generate::{default_scale, generate},
+};
+use criterion::{
+    criterion_group, criterion_main, AxisScale, BatchSize, BenchmarkId, Criterion,
+    PlotConfiguration,
+};
+
+/// Generated input of a day, the same for every run
+fn generated_input(day: u8, scale: usize) -> String {
+    generate(day, scale, 0).unwrap()
+}
+
+/// Stones of the real input size, over more and more blinks
+fn bench_day11_blinks(c: &mut Criterion) {
+    let input = generated_input(11, default_scale(11).unwrap());
+    let stones = day11::parse(&input).unwrap();
+
+    let mut group = c.benchmark_group("day11_blinks");
+    for blinks in [5, 15, 25, 35, 45, 55, 65, 75] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(blinks),
+            &blinks,
+            |b, &blinks| b.iter(|| day11::count_stones(&stones, blinks)),
+        );
+    }
+    group.finish();
+}
+
+/// Gardens of increasing side, regions found with a disjoint set or a flood fill
+fn bench_day12_sides(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day12_sides");
+    group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for side in [35, 70, 140, 280] {
+        let input = generated_input(12, side);
+        group.bench_with_input(BenchmarkId::new("part2", side), &input, |b, input| {
+            b.iter(|| day12::part2(input))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("part2_flood_fill", side),
+            &input,
+            |b, input| b.iter(|| day12::part2_flood_fill(input)),
+        );
+    }
+    group.finish();
+}
+
+/// Both parts over memory spaces of increasing side, part 2 removes bytes until there is a path
+/// again, its DFS variant looks for a path after each byte
+fn bench_day18_sides(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day18_sides");
+    group
+        .sample_size(10)
+        .plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for side in [33, 50, 71, 100] {
+        let input = generated_input(18, side);
+        group.bench_with_input(BenchmarkId::new("part1", side), &input, |b, input| {
+            b.iter(|| day18::part1_solve(day18::parse_inner(input, side).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("part2", side), &input, |b, input| {
+            b.iter(|| day18::part2_solve(day18::parse_inner(input, side).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("part2_dfs", side), &input, |b, input| {
+            b.iter(|| day18::part2_dfs_solve(day18::parse_inner(input, side).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+/// Racetrack of the real input size, with cheats of increasing radius
+fn bench_day20_radii(c: &mut Criterion) {
+    let input = generated_input(20, default_scale(20).unwrap());
+
+    let mut group = c.benchmark_group("day20_radii");
+    for radius in [2, 5, 10, 15, 20] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(radius),
+            &radius,
+            |b, &radius| {
+                b.iter_batched(
+                    || day20::parse(&input).unwrap(),
+                    |racetrack| day20::count_cheats(racetrack, radius, 100),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Both parts with more and more buyers
+fn bench_day22_buyers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day22_buyers");
+    group.plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+    for buyers in [250, 500, 1000, 2000, 4000] {
+        let input = generated_input(22, buyers);
+        group.bench_with_input(BenchmarkId::new("part1", buyers), &input, |b, input| {
+            b.iter(|| day22::part1(input))
+        });
+        group.bench_with_input(BenchmarkId::new("part2", buyers), &input, |b, input| {
+            b.iter(|| day22::part2(input))
+        });
+    }
+    group.finish();
+}
+
+/// Days split across threads, on pools of more and more threads. Only with the `parallel`
+/// feature, without it every size runs on the current thread
+#[cfg(feature = "parallel")]
+fn bench_threads(c: &mut Criterion) {
+    use aoc24::{day06, day07};
+
+    let [day06_input, day07_input, day20_input, day22_input] =
+        [6, 7, 20, 22].map(|day| generated_input(day, default_scale(day).unwrap()));
+
+    let mut group = c.benchmark_group("threads");
+    group.sample_size(10);
+    for threads in [1, 2, 4, 8] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let id = |name| BenchmarkId::new(name, threads);
+        group.bench_function(id("day06_part2"), |b| {
+            b.iter(|| pool.install(|| day06::part2(&day06_input)))
+        });
+        group.bench_function(id("day07_part2"), |b| {
+            b.iter(|| pool.install(|| day07::part2(&day07_input)))
+        });
+        group.bench_function(id("day20_part2"), |b| {
+            b.iter(|| pool.install(|| day20::part2(&day20_input)))
+        });
+        group.bench_function(id("day22_part1"), |b| {
+            b.iter(|| pool.install(|| day22::part1(&day22_input)))
+        });
+        group.bench_function(id("day22_part2"), |b| {
+            b.iter(|| pool.install(|| day22::part2(&day22_input)))
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn bench_threads(_c: &mut Criterion) {
+    eprintln!("Skipping threads: build with `--features parallel`");
+}
+
+criterion_group!(
+    benches,
+    bench_day11_blinks,
+    bench_day12_sides,
+    bench_day18_sides,
+    bench_day20_radii,
+    bench_day22_buyers,
+    bench_threads
+);
+criterion_main!(benches);
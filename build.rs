@@ -0,0 +1,31 @@
+fn main() {
+    #[cfg(feature = "header")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+
+        // Only the files of the C ABI, the rest of the crate has nothing to export
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{crate_dir}/src/ffi.rs"))
+            .with_src(format!("{crate_dir}/src/solve.rs"))
+            .generate()
+            .expect("Unable to generate the C header")
+            .write_to_file(format!("{crate_dir}/include/aoc24.h"));
+    }
+
+    // Tests of the real inputs are ignored when the input is missing, inputs are not committed
+    for day in 1..=25 {
+        let cfg = format!("input_day{day:02}");
+        println!("cargo::rustc-check-cfg=cfg({cfg})");
+        let path = format!("inputs/day{day:02}.txt");
+        if std::fs::read_to_string(path).is_ok_and(|input| !input.trim().is_empty()) {
+            println!("cargo::rustc-cfg={cfg}");
+        }
+    }
+
+    println!("cargo::rerun-if-changed=src/ffi.rs");
+    println!("cargo::rerun-if-changed=src/solve.rs");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    println!("cargo::rerun-if-changed=inputs");
+}
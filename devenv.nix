@@ -37,6 +37,9 @@
       # Add the day to the benches template
       sed -i "s/\(benches!(.*\));/\1, $DAY);/" ./benches/bench_days.rs
 
+      # Register the day in the library entry point
+      sed -i "s/\(solvers!(.*\));/\1, $DAY);/" ./src/solve.rs
+
       # Uncomment the day in the main file
       sed -i "s/\/\/ $DAY =>/$DAY =>/" ./src/main.rs
 
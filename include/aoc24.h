@@ -0,0 +1,98 @@
+/* Generated by cbindgen from src/ffi.rs and src/solve.rs, run `cargo build --features header` to update */
+
+#ifndef AOC24_H
+#define AOC24_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Number of days in the calendar
+ */
+#define AOC_N_DAYS 25
+
+/**
+ * Status returned by the functions of the C ABI, values are stable
+ */
+typedef enum AocStatus {
+  AOC_STATUS_OK = 0,
+  /**
+   * The day is not part of the calendar
+   */
+  AOC_STATUS_INVALID_DAY = 1,
+  /**
+   * Puzzles only have a part 1 and a part 2
+   */
+  AOC_STATUS_INVALID_PART = 2,
+  /**
+   * The day is part of the calendar but has not been solved yet
+   */
+  AOC_STATUS_NOT_IMPLEMENTED = 3,
+  /**
+   * The input of the day is malformed, the location is written to the output buffer
+   */
+  AOC_STATUS_PARSE_ERROR = 4,
+  /**
+   * The input is not valid UTF-8
+   */
+  AOC_STATUS_INVALID_UTF8 = 5,
+  /**
+   * A required pointer is null
+   */
+  AOC_STATUS_NULL_POINTER = 6,
+  /**
+   * The output buffer cannot hold the result, `out_len` is set to the required size
+   */
+  AOC_STATUS_BUFFER_TOO_SMALL = 7,
+  /**
+   * The solver panicked
+   */
+  AOC_STATUS_PANIC = 8,
+  /**
+   * The input is well-formed but the puzzle has no answer for it
+   */
+  AOC_STATUS_NO_ANSWER = 9,
+} AocStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Solve `part` of `day` with the `len` bytes at `input`, which must be UTF-8.
+ *
+ * `*out_len` is the capacity of `out_buf`. The answer, or the error message on failure, is
+ * written to `out_buf` as a nul-terminated string and `*out_len` is set to its length, without
+ * the nul terminator. When the buffer is too small, nothing is written, `*out_len` is still set
+ * and the `BufferTooSmall` status is returned, unless solving failed in the first place.
+ *
+ * # Safety
+ *
+ * `input` must point to `len` readable bytes, `out_len` must be valid for reads and writes and
+ * `out_buf` must point to `*out_len` writable bytes.
+ */
+enum AocStatus aoc_solve(uint8_t day,
+                         uint8_t part,
+                         const uint8_t *input,
+                         size_t len,
+                         char *out_buf,
+                         size_t *out_len);
+
+/**
+ * Static nul-terminated description of a status
+ */
+const char *aoc_status_str(enum AocStatus status);
+
+/**
+ * Whether `day` is implemented and can be passed to `aoc_solve`
+ */
+bool aoc_is_implemented(uint8_t day);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOC24_H */
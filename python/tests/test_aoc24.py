@@ -0,0 +1,69 @@
+"""Tests of the Python bindings, run with `python -m unittest discover python/tests`
+after `maturin develop`."""
+
+import unittest
+
+import aoc24
+from aoc24.day01 import part1, part2
+
+DAY01 = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"
+
+DAY17 = """Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+"""
+
+
+class TestDays(unittest.TestCase):
+    def test_parts(self):
+        self.assertEqual(part1(DAY01), 11)
+        self.assertEqual(part2(DAY01), 31)
+        self.assertEqual(aoc24.day01.part1(DAY01), 11)
+
+    def test_solve(self):
+        self.assertEqual(aoc24.solve(1, 2, DAY01), 31)
+        self.assertEqual(aoc24.solve(17, 1, DAY17), "4,6,3,5,6,3,5,2,1,0")
+
+    def test_errors(self):
+        with self.assertRaisesRegex(aoc24.ParseError, "line 2 col 7: expected `v=`"):
+            aoc24.day14.part1("p=0,4 v=3,-3\np=6,3 w=-1,-3\n")
+        with self.assertRaisesRegex(ValueError, "invalid part 3"):
+            aoc24.solve(1, 3, DAY01)
+        with self.assertRaisesRegex(ValueError, "day 25 is not implemented"):
+            aoc24.solve(25, 1, "")
+
+    def test_metadata(self):
+        self.assertEqual(aoc24.implemented_days(), list(range(1, 23)))
+
+        puzzles = aoc24.puzzles()
+        self.assertEqual(len(puzzles), 25)
+        self.assertEqual(
+            puzzles[0],
+            {
+                "day": 1,
+                "title": "Historian Hysteria",
+                "url": "https://adventofcode.com/2024/day/1",
+                "implemented": True,
+            },
+        )
+
+
+class TestUtils(unittest.TestCase):
+    def test_manhattan_distance(self):
+        self.assertEqual(
+            aoc24.utils.at_manhattan_distance((1, 1), 1, 0, 4),
+            [(0, 1), (1, 2), (2, 1), (1, 0)],
+        )
+        self.assertEqual(
+            len(aoc24.utils.within_manhattan_distance((1, 1), 2, 0, 4)), 11
+        )
+
+    def test_number_of_digits(self):
+        self.assertEqual(aoc24.utils.number_of_digits(9), 1)
+        self.assertEqual(aoc24.utils.number_of_digits(1000), 4)
+
+
+if __name__ == "__main__":
+    unittest.main()
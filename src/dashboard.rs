@@ -0,0 +1,464 @@
+use aoc24::{puzzle, solve, Puzzle, SolveError, N_DAYS};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Sparkline},
+    DefaultTerminal, Frame,
+};
+use std::{
+    any::Any,
+    fs,
+    io::{self, Write},
+    panic,
+    time::{Duration, Instant},
+};
+use tracing::info_span;
+
+/// Every run is appended to this file so that timings can be compared across sessions
+const HISTORY_PATH: &str = "./dashboard_history.txt";
+
+/// Number of past runs displayed in the sparklines
+const HISTORY_LEN: usize = 40;
+
+const GRID_COLUMNS: usize = 5;
+
+#[derive(Default)]
+enum Outcome {
+    #[default]
+    NotRun,
+    Running,
+    Answer(String),
+    /// The solver reported an error, such as a malformed input
+    Failed(SolveError),
+    /// The solver panicked, with the panic message
+    Panicked(String),
+}
+
+#[derive(Default)]
+struct Part {
+    outcome: Outcome,
+    /// Answer accepted by adventofcode.com, if the solved puzzle description was downloaded
+    expected: Option<String>,
+    /// Past run durations in microseconds, oldest first
+    history: Vec<u64>,
+}
+
+#[derive(Clone, Copy)]
+enum Status {
+    NotImplemented,
+    MissingInput,
+    NotRun,
+    Running,
+    Correct,
+    Wrong,
+    Unverified,
+    Failed,
+}
+
+impl Status {
+    fn symbol(self) -> &'static str {
+        match self {
+            Status::NotImplemented => " ",
+            Status::MissingInput => "∅",
+            Status::NotRun => "·",
+            Status::Running => "…",
+            Status::Correct => "✓",
+            Status::Wrong => "✗",
+            Status::Unverified => "?",
+            Status::Failed => "!",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Status::NotImplemented => "not implemented",
+            Status::MissingInput => "missing input",
+            Status::NotRun => "not run",
+            Status::Running => "running",
+            Status::Correct => "correct",
+            Status::Wrong => "wrong answer",
+            Status::Unverified => "unverified",
+            Status::Failed => "failed",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Status::NotImplemented | Status::MissingInput | Status::NotRun => Color::DarkGray,
+            Status::Running => Color::Cyan,
+            Status::Correct => Color::Green,
+            Status::Unverified => Color::Yellow,
+            Status::Wrong | Status::Failed => Color::Red,
+        }
+    }
+}
+
+struct Day {
+    puzzle: Puzzle,
+    input: Option<String>,
+    parts: [Part; 2],
+}
+
+impl Day {
+    fn load(day: u8) -> Self {
+        let puzzle = puzzle(day).expect("day is part of the calendar");
+        let input = fs::read_to_string(format!("./inputs/day{:0>2}.txt", day)).ok();
+        let [expected1, expected2] = expected_answers(day);
+
+        Self {
+            puzzle,
+            input,
+            parts: [
+                Part {
+                    expected: expected1,
+                    ..Default::default()
+                },
+                Part {
+                    expected: expected2,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    fn status(&self, part: usize) -> Status {
+        if !self.puzzle.implemented {
+            return Status::NotImplemented;
+        }
+        if self.input.is_none() {
+            return Status::MissingInput;
+        }
+
+        let part = &self.parts[part];
+        match (&part.outcome, &part.expected) {
+            (Outcome::NotRun, _) => Status::NotRun,
+            (Outcome::Running, _) => Status::Running,
+            (Outcome::Failed(_) | Outcome::Panicked(_), _) => Status::Failed,
+            (Outcome::Answer(answer), Some(expected)) if answer == expected => Status::Correct,
+            (Outcome::Answer(_), Some(_)) => Status::Wrong,
+            (Outcome::Answer(_), None) => Status::Unverified,
+        }
+    }
+
+    fn is_runnable(&self) -> bool {
+        self.puzzle.implemented && self.input.is_some()
+    }
+}
+
+/// Answers accepted by adventofcode.com, read from the puzzle description downloaded with
+/// `aoc download` once the parts are solved
+fn expected_answers(day: u8) -> [Option<String>; 2] {
+    let puzzle = [
+        format!("./puzzles/day{}.md", day),
+        format!("./puzzles/day{:0>2}.md", day),
+    ]
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .unwrap_or_default();
+
+    let mut answers = puzzle
+        .split("Your puzzle answer was")
+        .skip(1)
+        .filter_map(|s| {
+            s.trim_start()
+                .trim_start_matches("<code>")
+                .trim_start_matches('`')
+                .split(['`', '<', ' ', '\n'])
+                .next()
+                .map(|answer| answer.trim_end_matches('.').to_string())
+                .filter(|answer| !answer.is_empty())
+        });
+
+    [answers.next(), answers.next()]
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+struct Dashboard {
+    days: Vec<Day>,
+    selected: usize,
+}
+
+impl Dashboard {
+    fn new() -> Self {
+        let mut days = (1..=N_DAYS).map(Day::load).collect::<Vec<_>>();
+
+        if let Ok(history) = fs::read_to_string(HISTORY_PATH) {
+            history.lines().for_each(|line| {
+                let mut fields = line.split(' ').flat_map(|f| f.parse::<u64>());
+                if let (Some(day), Some(part), Some(micros)) =
+                    (fields.next(), fields.next(), fields.next())
+                {
+                    if (1..=N_DAYS as u64).contains(&day) && (1..=2).contains(&part) {
+                        days[day as usize - 1].parts[part as usize - 1]
+                            .history
+                            .push(micros);
+                    }
+                }
+            });
+        }
+
+        Self { days, selected: 0 }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let n_days = self.days.len();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.selected = (self.selected + 1).min(n_days - 1);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = self.selected.saturating_sub(GRID_COLUMNS);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.selected = (self.selected + GRID_COLUMNS).min(n_days - 1);
+                }
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    self.run_parts(terminal, &[(self.selected, 0), (self.selected, 1)])?;
+                }
+                KeyCode::Char('1') => self.run_parts(terminal, &[(self.selected, 0)])?,
+                KeyCode::Char('2') => self.run_parts(terminal, &[(self.selected, 1)])?,
+                KeyCode::Char('a') => {
+                    let all = (0..n_days)
+                        .flat_map(|day| [(day, 0), (day, 1)])
+                        .collect::<Vec<_>>();
+                    self.run_parts(terminal, &all)?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Run the given `(day index, part index)` pairs, redrawing after each of them
+    fn run_parts(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        parts: &[(usize, usize)],
+    ) -> io::Result<()> {
+        let parts = parts
+            .iter()
+            .filter(|(day, _)| self.days[*day].is_runnable())
+            .collect::<Vec<_>>();
+
+        for &&(day, part) in parts.iter() {
+            self.days[day].parts[part].outcome = Outcome::Running;
+        }
+
+        for &&(day, part) in parts.iter() {
+            terminal.draw(|frame| self.draw(frame))?;
+            self.run_part(day, part)?;
+        }
+
+        Ok(())
+    }
+
+    fn run_part(&mut self, day: usize, part: usize) -> io::Result<()> {
+        let Day {
+            puzzle,
+            input: Some(input),
+            parts,
+        } = &mut self.days[day]
+        else {
+            return Ok(());
+        };
+        let day_number = puzzle.day;
+        let part_number = part as u8 + 1;
+
+        // Keep the default hook from printing over the dashboard, the message is displayed in the
+        // details panel instead
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let start = Instant::now();
+        let result = info_span!("day", day = day_number).in_scope(|| {
+            info_span!("part", part = part_number)
+                .in_scope(|| panic::catch_unwind(|| solve(day_number, part_number, input)))
+        });
+        let elapsed = start.elapsed();
+        panic::set_hook(hook);
+
+        let part_state = &mut parts[part];
+        match result {
+            Ok(Ok(answer)) => {
+                let micros = elapsed.as_micros() as u64;
+                part_state.outcome = Outcome::Answer(answer.to_string());
+                part_state.history.push(micros);
+
+                let mut history = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(HISTORY_PATH)?;
+                writeln!(history, "{} {} {}", day_number, part_number, micros)?;
+            }
+            Ok(Err(e)) => part_state.outcome = Outcome::Failed(e),
+            Err(payload) => part_state.outcome = Outcome::Panicked(panic_message(payload)),
+        }
+
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let rows = self.days.len().div_ceil(GRID_COLUMNS) as u16;
+        let [grid_area, details_area, help_area] = Layout::vertical([
+            Constraint::Length(rows * 4),
+            Constraint::Min(9),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let row_areas =
+            Layout::vertical(vec![Constraint::Length(4); rows as usize]).split(grid_area);
+        for (row, row_area) in row_areas.iter().enumerate() {
+            let cell_areas = Layout::horizontal(vec![
+                Constraint::Ratio(1, GRID_COLUMNS as u32);
+                GRID_COLUMNS
+            ])
+            .split(*row_area);
+            for (column, cell_area) in cell_areas.iter().enumerate() {
+                let index = row * GRID_COLUMNS + column;
+                if let Some(day) = self.days.get(index) {
+                    self.draw_cell(frame, *cell_area, day, index == self.selected);
+                }
+            }
+        }
+
+        self.draw_details(frame, details_area, &self.days[self.selected]);
+
+        frame.render_widget(
+            Paragraph::new(
+                " ←↓↑→/hjkl select · enter/r run day · 1/2 run part · a run all · q quit",
+            )
+            .style(Style::new().fg(Color::DarkGray)),
+            help_area,
+        );
+    }
+
+    fn draw_cell(&self, frame: &mut Frame, area: Rect, day: &Day, selected: bool) {
+        let border_style = if selected {
+            Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::new().fg(Color::DarkGray)
+        };
+
+        let lines = (0..2)
+            .map(|part| {
+                let status = day.status(part);
+                let timing = match (&day.parts[part].outcome, day.parts[part].history.last()) {
+                    (Outcome::Answer(_), Some(&micros)) => {
+                        format!("{:.2?}", Duration::from_micros(micros))
+                    }
+                    _ => String::new(),
+                };
+
+                Line::from(vec![
+                    Span::raw(format!("{} ", part + 1)),
+                    Span::styled(status.symbol(), Style::new().fg(status.color())),
+                    Span::raw(format!(" {}", timing)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .title(format!(" Day {:0>2} ", day.puzzle.day))
+                    .border_style(border_style),
+            ),
+            area,
+        );
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect, day: &Day) {
+        let block = Block::bordered().title(format!(
+            " Day {:0>2}: {} ",
+            day.puzzle.day, day.puzzle.title
+        ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let part_areas =
+            Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(inner);
+
+        for (part, part_area) in part_areas.iter().enumerate() {
+            let state = &day.parts[part];
+            let status = day.status(part);
+
+            let [text_area, sparkline_area] =
+                Layout::vertical([Constraint::Length(4), Constraint::Min(1)]).areas(*part_area);
+
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!("Part {} ", part + 1),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(status.label(), Style::new().fg(status.color())),
+            ])];
+            match &state.outcome {
+                Outcome::Answer(answer) => lines.push(Line::from(format!("Answer:   {}", answer))),
+                Outcome::Failed(error) => {
+                    let message = match error {
+                        SolveError::Parse(e) => format!("Parse error: {}", e),
+                        SolveError::NoAnswer => "No answer".to_string(),
+                        e => format!("Error:    {}", e),
+                    };
+                    lines.push(Line::styled(message, Style::new().fg(Color::Red)));
+                }
+                Outcome::Panicked(message) => lines.push(Line::styled(
+                    format!("Panic:    {}", message),
+                    Style::new().fg(Color::Red),
+                )),
+                Outcome::NotRun | Outcome::Running => {}
+            }
+            if let Some(expected) = &state.expected {
+                lines.push(Line::from(format!("Expected: {}", expected)));
+            }
+            if let Some(&micros) = state.history.last() {
+                lines.push(Line::from(format!(
+                    "Timing:   {:.2?} (last of {} runs)",
+                    Duration::from_micros(micros),
+                    state.history.len()
+                )));
+            }
+            frame.render_widget(Paragraph::new(lines), text_area);
+
+            let recent = &state.history[state.history.len().saturating_sub(HISTORY_LEN)..];
+            frame.render_widget(
+                Sparkline::default()
+                    .data(recent)
+                    .style(Style::new().fg(Color::Cyan)),
+                sparkline_area,
+            );
+        }
+    }
+}
+
+pub fn run() -> io::Result<()> {
+    let mut dashboard = Dashboard::new();
+    let mut terminal = ratatui::init();
+    let result = dashboard.run(&mut terminal);
+    ratatui::restore();
+    result
+}
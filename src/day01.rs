@@ -1,14 +1,28 @@
 use std::collections::HashMap;
+use tracing::info_span;
 
-pub fn part1(input: &str) -> u32 {
-    let mut first_list = Vec::new();
-    let mut second_list = Vec::new();
-    for line in input.lines() {
-        let (first, second) = line.split_once("   ").unwrap();
-        first_list.push(first.parse::<u32>().unwrap());
-        second_list.push(second.parse::<u32>().unwrap());
-    }
+use crate::utils::{integer_array, ParseError};
+
+/// Sums of `u32` distances and products, which overflow a `u32`
+type Num = u64;
+
+fn parse_line(input: &str, line: &str) -> Result<(u32, u32), ParseError> {
+    let [first, second] = integer_array(input, line, "two numbers separated by `   `")?;
+
+    Ok((first, second))
+}
+
+/// Left and right lists
+pub fn parse(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    input.lines().map(|line| parse_line(input, line)).collect()
+}
 
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let lists = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(lists)))
+}
+
+pub fn part1_solve((mut first_list, mut second_list): (Vec<u32>, Vec<u32>)) -> Num {
     first_list.sort();
     second_list.sort();
 
@@ -18,35 +32,28 @@ pub fn part1(input: &str) -> u32 {
         .map(|(i, first)| {
             let second = second_list[i];
 
-            second.abs_diff(first)
+            Num::from(second.abs_diff(first))
         })
         .sum()
 }
 
-pub fn part2(input: &str) -> u32 {
-    let mut first_list = Vec::new();
-    let mut second_list: HashMap<u32, u32> = HashMap::new();
-
-    for line in input.lines() {
-        let (first, second) = line
-            .split_once("   ")
-            .map(|(first, second)| {
-                (
-                    first.parse::<u32>().unwrap(),
-                    second.parse::<u32>().unwrap(),
-                )
-            })
-            .unwrap();
-
-        first_list.push(first);
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let lists = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(lists)))
+}
 
-        let second_entry = second_list.entry(second).or_default();
+pub fn part2_solve((first_list, second_list): (Vec<u32>, Vec<u32>)) -> Num {
+    let mut second_counts: HashMap<u32, u32> = HashMap::new();
+    for second in second_list {
+        let second_entry = second_counts.entry(second).or_default();
         *second_entry += 1;
     }
 
     first_list
         .into_iter()
-        .map(|number| number * second_list.get(&number).copied().unwrap_or_default())
+        .map(|number| {
+            Num::from(number) * Num::from(second_counts.get(&number).copied().unwrap_or_default())
+        })
         .sum()
 }
 
@@ -54,9 +61,7 @@ pub fn part2(input: &str) -> u32 {
 mod tests {
     use super::*;
 
-    #[test]
-    fn example() {
-        let example = "\
+    const EXAMPLE: &str = "\
 3   4
 4   3
 2   5
@@ -64,23 +69,35 @@ mod tests {
 3   9
 3   3
 ";
-        assert_eq!(part1(example), 11);
-        assert_eq!(part2(example), 31);
+
+    crate::utils::day_tests! {
+        day: 01,
+        examples: {
+            part1(EXAMPLE) => 11,
+            part2(EXAMPLE) => 31,
+        },
+        input: {
+            part1 => 936063,
+            part2 => 23150395,
+        },
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 936063);
+    fn large_numbers() {
+        let input = "4294967295   0\n4294967295   4294967295\n";
+        assert_eq!(part1(input), Ok(4294967295));
+        assert_eq!(part2(input), Ok(2 * 4294967295));
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 23150395);
+    fn malformed() {
+        assert_eq!(
+            part1("3   4\n4\n").unwrap_err().to_string(),
+            "line 2 col 1: expected two numbers separated by `   `"
+        );
+        assert_eq!(
+            part2("3   4\n4   -3\n").unwrap_err().to_string(),
+            "line 2 col 5: expected a number"
+        );
     }
 }
@@ -1,11 +1,33 @@
-pub fn part1(input: &str) -> u32 {
+use tracing::info_span;
+
+use crate::utils::{integers, ParseError};
+
+/// Reports of levels, with at least two levels each
+pub fn parse(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
     input
         .lines()
         .map(|line| {
-            let mut line_numbers = line
-                .split_whitespace()
-                .map(|n| n.parse::<u32>().unwrap())
-                .peekable();
+            let report = integers(input, line).collect::<Result<Vec<u32>, _>>()?;
+
+            if report.len() < 2 {
+                return Err(ParseError::at(input, line, "at least two numbers"));
+            }
+
+            Ok(report)
+        })
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+    let reports = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(reports)))
+}
+
+pub fn part1_solve(reports: Vec<Vec<u32>>) -> u32 {
+    reports
+        .into_iter()
+        .map(|line_numbers| {
+            let mut line_numbers = line_numbers.into_iter().peekable();
 
             let first = line_numbers.next().unwrap();
             let second = line_numbers.peek().unwrap();
@@ -39,14 +61,19 @@ pub fn part1(input: &str) -> u32 {
         .sum()
 }
 
-pub fn part2(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let line_numbers = line
-                .split_whitespace()
-                .map(|n| n.parse::<u32>().unwrap())
-                .collect::<Vec<_>>();
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+    let reports = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(reports)))
+}
+
+pub fn part2_solve(reports: Vec<Vec<u32>>) -> u32 {
+    reports
+        .into_iter()
+        .map(|line_numbers| {
+            if line_numbers.len() <= 2 {
+                // Removing a level leaves a single level, which is always safe
+                return 1;
+            }
 
             for i in 0..line_numbers.len() {
                 let mut line_numbers = line_numbers.clone();
@@ -89,9 +116,7 @@ pub fn part2(input: &str) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn example() {
-        let example = "\
+    const EXAMPLE: &str = "\
 7 6 4 2 1
 1 2 7 8 9
 9 7 6 2 1
@@ -100,8 +125,16 @@ mod tests {
 1 3 6 7 9
 ";
 
-        assert_eq!(part1(example), 2);
-        assert_eq!(part2(example), 4);
+    crate::utils::day_tests! {
+        day: 02,
+        examples: {
+            part1(EXAMPLE) => 2,
+            part2(EXAMPLE) => 4,
+        },
+        input: {
+            part1 => 432,
+            part2 => 488,
+        },
     }
 
     #[test]
@@ -115,23 +148,11 @@ mod tests {
 9 7 6 2 1
 ";
 
-        assert_eq!(part2(should_match), 1);
-        assert_eq!(part2(should_not_match), 0);
-    }
+        assert_eq!(part2(should_match), Ok(1));
+        assert_eq!(part2(should_not_match), Ok(0));
 
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 432);
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 488);
+        // Two levels are always safe once one is removed
+        assert_eq!(part1("1 5\n"), Ok(0));
+        assert_eq!(part2("1 5\n"), Ok(1));
     }
 }
@@ -1,61 +1,79 @@
 use regex;
 use std::sync::LazyLock;
+use tracing::info_span;
 
-static MUL_REGEX_PART_1: LazyLock<regex::Regex> =
-    LazyLock::new(|| regex::Regex::new(r"mul\(\d+,\d+\)").unwrap());
+use crate::utils::{parse_number, ParseError};
 
-static MUL_REGEX_PART_2: LazyLock<regex::Regex> =
-    LazyLock::new(|| regex::Regex::new(r"do\(\)|don't\(\)|mul\(\d+,\d+\)").unwrap());
+static INSTRUCTION_REGEX: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"do\(\)|don't\(\)|mul\(\d{1,3},\d{1,3}\)").unwrap());
 
-pub fn part1(input: &str) -> usize {
+pub enum Instruction {
+    Do,
+    Dont,
+    Mul(usize, usize),
+}
+
+/// Instructions found in the corrupted memory, part 1 ignores `do()` and `don't()`
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
     input
         .lines()
-        .map(|line| {
-            MUL_REGEX_PART_1
-                .find_iter(line)
-                .map(|m| {
-                    let m = m.as_str();
-                    let Some((a, b)) = m[4..m.len() - 1].split_once(",") else {
-                        panic!("Failed to parse")
-                    };
-
-                    a.parse::<usize>().unwrap() * b.parse::<usize>().unwrap()
+        .flat_map(|line| {
+            INSTRUCTION_REGEX.find_iter(line).map(|m| {
+                Ok(match m.as_str() {
+                    "do()" => Instruction::Do,
+                    "don't()" => Instruction::Dont,
+                    m => {
+                        // The regex guarantees the `mul(a,b)` shape, with numbers of up to 3 digits
+                        let (a, b) = m[4..m.len() - 1]
+                            .split_once(",")
+                            .ok_or_else(|| ParseError::at(input, m, "`mul(a,b)`"))?;
+                        Instruction::Mul(parse_number(input, a)?, parse_number(input, b)?)
+                    }
                 })
-                .sum::<usize>()
+            })
+        })
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+    let instructions = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(instructions)))
+}
+
+pub fn part1_solve(instructions: Vec<Instruction>) -> usize {
+    instructions
+        .into_iter()
+        .map(|instruction| match instruction {
+            Instruction::Mul(a, b) => a * b,
+            _ => 0,
         })
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+    let instructions = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(instructions)))
+}
+
+pub fn part2_solve(instructions: Vec<Instruction>) -> usize {
     let mut enabled = true;
-    input
-        .lines()
-        .map(|line| {
-            MUL_REGEX_PART_2
-                .find_iter(line)
-                .map(|m| {
-                    let m = m.as_str();
-                    match m {
-                        "do()" => {
-                            enabled = true;
-                            0
-                        }
-                        "don't()" => {
-                            enabled = false;
-                            0
-                        }
-                        m => {
-                            if !enabled {
-                                return 0;
-                            }
-                            let Some((a, b)) = m[4..m.len() - 1].split_once(",") else {
-                                panic!("Failed to parse")
-                            };
-                            a.parse::<usize>().unwrap() * b.parse::<usize>().unwrap()
-                        }
-                    }
-                })
-                .sum::<usize>()
+    instructions
+        .into_iter()
+        .map(|instruction| match instruction {
+            Instruction::Do => {
+                enabled = true;
+                0
+            }
+            Instruction::Dont => {
+                enabled = false;
+                0
+            }
+            Instruction::Mul(a, b) => {
+                if !enabled {
+                    return 0;
+                }
+                a * b
+            }
         })
         .sum()
 }
@@ -64,37 +82,29 @@ pub fn part2(input: &str) -> usize {
 mod tests {
     use super::*;
 
-    #[test]
-    fn part1_example() {
-        let example = "\
+    const EXAMPLE: &str = "\
 xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
 ";
 
-        assert_eq!(part1(example), 161);
-    }
-
-    #[test]
-    fn part2_example() {
-        let example = "\
+    const EXAMPLE_2: &str = "\
 xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
 ";
 
-        assert_eq!(part2(example), 48);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 169021493);
+    crate::utils::day_tests! {
+        day: 03,
+        examples: {
+            part1(EXAMPLE) => 161,
+            part2(EXAMPLE_2) => 48,
+        },
+        input: {
+            part1 => 169021493,
+            part2 => 111762583,
+        },
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 111762583);
+    fn long_numbers() {
+        // Numbers have 1 to 3 digits, longer ones do not make an instruction
+        assert_eq!(part1("mul(999,999)mul(1000,2)mul(2,0001)"), Ok(998001));
     }
 }
@@ -1,15 +1,20 @@
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
+use tracing::info_span;
+
+use crate::utils::{Direction8, Grid, ParseError};
+
+/// Grid of letters, with lines of the same length
+pub fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::from_bytes(input)
 }
 
 struct ExplorationStatus {
     starting_point: (isize, isize),
     direction: (isize, isize),
-    seeked_letter: char,
+    seeked_letter: u8,
 }
 
 fn explore(
-    input: &Vec<Vec<char>>,
+    input: &Grid<u8>,
     ExplorationStatus {
         starting_point,
         direction,
@@ -21,9 +26,9 @@ fn explore(
         starting_point.1 + direction.1,
     );
     if to_explore.0 < 0
-        || to_explore.0 >= input.len() as isize
+        || to_explore.0 >= input.height() as isize
         || to_explore.1 < 0
-        || to_explore.1 >= input[0].len() as isize
+        || to_explore.1 >= input.width() as isize
     {
         return 0;
     }
@@ -32,7 +37,7 @@ fn explore(
         return 0;
     }
 
-    if seeked_letter == 'S' {
+    if seeked_letter == b'S' {
         return 1;
     }
 
@@ -42,39 +47,33 @@ fn explore(
             starting_point: to_explore,
             direction,
             seeked_letter: match seeked_letter {
-                'M' => 'A',
-                'A' => 'S',
+                b'M' => b'A',
+                b'A' => b'S',
                 _ => unreachable!(),
             },
         },
     )
 }
 
-pub fn part1(input: &str) -> usize {
-    let input = parse_input(input);
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
+}
+
+pub fn part1_solve(input: Grid<u8>) -> usize {
     let mut count = 0;
 
-    for i in 0..input.len() {
-        for j in 0..input[i].len() {
-            if input[i][j] == 'X' {
-                for direction in [
-                    (0, 1),
-                    (1, 0),
-                    (0, -1),
-                    (-1, 0),
-                    (1, 1),
-                    (1, -1),
-                    (-1, 1),
-                    (-1, -1),
-                ]
-                .iter()
-                {
+    for i in 0..input.height() {
+        for j in 0..input.width() {
+            if input[i][j] == b'X' {
+                for direction in Direction8::ALL {
+                    let (di, dj) = direction.delta();
                     count += explore(
                         &input,
                         ExplorationStatus {
                             starting_point: (i as isize, j as isize),
-                            direction: *direction,
-                            seeked_letter: 'M',
+                            direction: (di as isize, dj as isize),
+                            seeked_letter: b'M',
                         },
                     );
                 }
@@ -85,23 +84,27 @@ pub fn part1(input: &str) -> usize {
     count
 }
 
-pub fn part2(input: &str) -> usize {
-    let input = parse_input(input);
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(parsed)))
+}
+
+pub fn part2_solve(input: Grid<u8>) -> usize {
     let mut count = 0;
 
-    (1..(input.len() - 1)).for_each(|i| {
-        (1..input[i].len() - 1).for_each(|j| {
-            if input[i][j] == 'A' {
+    (1..(input.height() - 1)).for_each(|i| {
+        (1..input.width() - 1).for_each(|j| {
+            if input[i][j] == b'A' {
                 let top_left = input[i - 1][j - 1];
                 let top_right = input[i - 1][j + 1];
                 let bottom_left = input[i + 1][j - 1];
                 let bottom_right = input[i + 1][j + 1];
 
                 match (top_left, top_right, bottom_right, bottom_left) {
-                    ('M', 'M', 'S', 'S')
-                    | ('S', 'M', 'M', 'S')
-                    | ('S', 'S', 'M', 'M')
-                    | ('M', 'S', 'S', 'M') => count += 1,
+                    (b'M', b'M', b'S', b'S')
+                    | (b'S', b'M', b'M', b'S')
+                    | (b'S', b'S', b'M', b'M')
+                    | (b'M', b'S', b'S', b'M') => count += 1,
                     _ => {}
                 }
             }
@@ -115,9 +118,7 @@ pub fn part2(input: &str) -> usize {
 mod tests {
     use super::*;
 
-    #[test]
-    fn example() {
-        let example = "\
+    const EXAMPLE: &str = "\
 MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
@@ -130,23 +131,15 @@ MAMMMXMMMM
 MXMXAXMASX
 ";
 
-        assert_eq!(part1(example), 18);
-        assert_eq!(part2(example), 9);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 2378);
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 1796);
+    crate::utils::day_tests! {
+        day: 04,
+        examples: {
+            part1(EXAMPLE) => 18,
+            part2(EXAMPLE) => 9,
+        },
+        input: {
+            part1 => 2378,
+            part2 => 1796,
+        },
     }
 }
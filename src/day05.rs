@@ -1,44 +1,60 @@
-use std::collections::{HashMap, HashSet};
+use rustc_hash::{FxHashMap, FxHashSet};
+use tracing::info_span;
+
+use crate::{
+    utils::{integer_array, integers, ParseError},
+    SolveError,
+};
 
 type Number = u32;
 
-struct Input {
+/// Pages that must be before each page, pages are small numbers that hash fast
+type Rules = FxHashMap<Number, FxHashSet<Number>>;
+
+/// Ordering rules and updates of pages
+pub struct Input {
     /// Each entry is the set of pages that **must** be before the key IF they are present in the update
-    rules: HashMap<Number, HashSet<Number>>,
+    rules: Rules,
     updates: Vec<Vec<Number>>,
 }
 
-fn parse_input(input: &str) -> Input {
-    let (rules, lists) = input.split_once("\n\n").unwrap();
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let (rules, lists) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::end(input, "an empty line between rules and updates"))?;
 
-    let rules: HashMap<Number, HashSet<Number>> =
-        rules.lines().fold(HashMap::new(), |mut rules, line| {
-            let (first, second): (Number, Number) = line
-                .split_once("|")
-                .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
-                .unwrap();
+    let rules: Rules = rules
+        .lines()
+        .try_fold(Rules::default(), |mut rules: Rules, line| {
+            let [first, second] = integer_array(input, line, "two pages separated by `|`")?;
 
             rules.entry(second).or_default().insert(first);
 
-            rules
-        });
+            Ok(rules)
+        })?;
 
     let updates: Vec<Vec<Number>> = lists
         .lines()
         .map(|line| {
-            line.split(",")
-                .map(|n| n.parse().unwrap())
-                .collect::<Vec<Number>>()
+            let update = integers(input, line).collect::<Result<Vec<Number>, _>>()?;
+            if update.is_empty() {
+                return Err(ParseError::at(input, line, "pages separated by `,`"));
+            }
+
+            Ok(update)
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    Input { rules, updates }
+    Ok(Input { rules, updates })
 }
 
-pub fn part1_first_implem(input: &str) -> Number {
-    let Input { rules, updates } = parse_input(input);
+/// First implementation of part 1, checking each page against the rules of the pages before it
+pub fn part1_first_implem(input: &str) -> Result<Number, ParseError> {
+    let Input { rules, updates } = info_span!("parse").in_scope(|| parse(input))?;
+
+    let _solve_span = info_span!("solve").entered();
 
-    updates.iter().fold(0, |sum, update| {
+    Ok(updates.iter().fold(0, |sum, update| {
         for (i, page) in update.iter().enumerate() {
             // Check that rules are satisfied for this page, aka the current page should not be
             // placed before any already placed pages
@@ -52,70 +68,83 @@ pub fn part1_first_implem(input: &str) -> Number {
         }
 
         sum + update[update.len() / 2]
-    })
+    }))
 }
 
-pub fn part1(input: &str) -> Number {
-    let Input { rules, updates } = parse_input(input);
-
-    // Make a custom sort that respects the rules
-    let sorter = |a: &Number, b: &Number| {
-        let Some(rule) = rules.get(a) else {
-            return std::cmp::Ordering::Less;
-        };
-
-        if rule.contains(b) {
-            std::cmp::Ordering::Greater
-        } else {
-            std::cmp::Ordering::Less
-        }
-    };
+pub fn part1(input: &str) -> Result<Number, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
+}
 
+pub fn part1_solve(Input { rules, updates }: Input) -> Number {
     updates
-        .into_iter()
-        .map(|mut update| {
-            let snapshot = update.clone();
-
-            update.sort_unstable_by(sorter);
-
-            if update != snapshot {
-                0
-            } else {
-                update[update.len() / 2]
-            }
-        })
+        .iter()
+        .filter(|update| is_ordered(&rules, update))
+        .map(|update| update[update.len() / 2])
         .sum()
 }
 
-pub fn part2(input: &str) -> Number {
-    let Input { rules, updates } = parse_input(input);
-
-    // Make a custom sort that respects the rules
-    let sorter = |a: &Number, b: &Number| {
-        let Some(rule) = rules.get(a) else {
-            return std::cmp::Ordering::Less;
-        };
+/// Whether no page of `update` must be before a page placed earlier
+fn is_ordered(rules: &Rules, update: &[Number]) -> bool {
+    update.iter().enumerate().all(|(i, page)| {
+        rules
+            .get(page)
+            .is_none_or(|before| update[i + 1..].iter().all(|later| !before.contains(later)))
+    })
+}
 
-        if rule.contains(b) {
-            std::cmp::Ordering::Greater
-        } else {
-            std::cmp::Ordering::Less
+/// Pages of `update` in an order that follows the rules, `None` when the rules between its pages
+/// form a cycle. Rules are not transitive, only the pages of the update matter
+fn reorder(rules: &Rules, update: &[Number]) -> Option<Vec<Number>> {
+    // Whether a page must be before another, by their index in the update
+    let before = update
+        .iter()
+        .map(|page| {
+            let pages = rules.get(page);
+            update
+                .iter()
+                .map(|other| pages.is_some_and(|pages| pages.contains(other)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Number of pages that must be before each page and are not placed yet
+    let mut waiting = before
+        .iter()
+        .map(|pages| pages.iter().filter(|&&before| before).count())
+        .collect::<Vec<_>>();
+    let mut placed = vec![false; update.len()];
+
+    let mut ordered = Vec::with_capacity(update.len());
+    while ordered.len() < update.len() {
+        let next = (0..update.len()).find(|&i| !placed[i] && waiting[i] == 0)?;
+        placed[next] = true;
+        ordered.push(update[next]);
+
+        for (i, pages) in before.iter().enumerate() {
+            if pages[next] {
+                waiting[i] -= 1;
+            }
         }
-    };
+    }
 
-    updates
-        .into_iter()
-        .map(|mut update| {
-            let snapshot = update.clone();
+    Some(ordered)
+}
 
-            update.sort_unstable_by(sorter);
+pub fn part2(input: &str) -> Result<Number, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-            if update == snapshot {
-                0
-            } else {
-                update[update.len() / 2]
-            }
-        })
+/// Sum of the middle pages of the reordered updates, `None` when the rules of an update form a
+/// cycle
+pub fn part2_solve(Input { rules, updates }: Input) -> Option<Number> {
+    updates
+        .iter()
+        .filter(|update| !is_ordered(&rules, update))
+        .map(|update| reorder(&rules, update).map(|ordered| ordered[ordered.len() / 2]))
         .sum()
 }
 
@@ -123,9 +152,7 @@ pub fn part2(input: &str) -> Number {
 mod tests {
     use super::*;
 
-    #[test]
-    fn example() {
-        let example = "\
+    const EXAMPLE: &str = "\
 47|53
 97|13
 97|61
@@ -156,8 +183,16 @@ mod tests {
 97,13,75,29,47
 ";
 
-        assert_eq!(part1(example), 143);
-        assert_eq!(part2(example), 123);
+    crate::utils::day_tests! {
+        day: 05,
+        examples: {
+            part1(EXAMPLE) => 143,
+            part2(EXAMPLE) => 123,
+        },
+        input: {
+            part1 => 4135,
+            part2 => 5285,
+        },
     }
 
     #[test]
@@ -170,7 +205,7 @@ mod tests {
 1,2,3
 ";
 
-        assert_eq!(part2(example), 2);
+        assert_eq!(part2(example), Ok(2));
 
         let example = "\
 1|2
@@ -185,23 +220,26 @@ mod tests {
 4,3,7,1,2
 ";
 
-        assert_eq!(part1(example), 3);
-        assert_eq!(part2(example), 3);
+        assert_eq!(part1(example), Ok(3));
+        assert_eq!(part2(example), Ok(3));
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 4135);
+    fn malformed() {
+        assert_eq!(
+            part1("1|2\n3\n\n1,2\n").unwrap_err().to_string(),
+            "line 2 col 1: expected two pages separated by `|`"
+        );
+        assert_eq!(
+            part2("1|2\n\n1,2\n\n").unwrap_err().to_string(),
+            "line 4 col 1: expected pages separated by `,`"
+        );
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 5285);
+    fn cyclic_rules() {
+        let example = "1|2\n2|3\n3|1\n\n1,2\n3,2,1\n";
+        assert_eq!(part1(example), Ok(2));
+        assert_eq!(part2(example), Err(SolveError::NoAnswer));
     }
 }
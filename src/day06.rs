@@ -1,150 +1,176 @@
-const INPUT_SIZE: usize = 130;
+use tracing::{debug_span, info_span};
 
-type Input = ([[u8; INPUT_SIZE + 2]; INPUT_SIZE + 2], (usize, usize));
+use crate::{
+    utils::{par_fold, Direction, DirectionSet, Grid, ParseError},
+    SolveError,
+};
 
-#[derive(Debug, PartialEq, Hash, Eq, Clone, Copy)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
+/// Grid of `.` and `#`, and the start position
+type Input = (Grid<u8>, (usize, usize));
 
-impl Direction {
-    fn next_direction(&self) -> Self {
-        match self {
-            Self::North => Self::East,
-            Self::East => Self::South,
-            Self::South => Self::West,
-            Self::West => Self::North,
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let mut start_position = None;
+    let grid = Grid::parse(input, |position, c| match c {
+        b'^' if start_position.is_none() => {
+            start_position = Some(position);
+            Ok(b'.')
         }
-    }
+        b'#' | b'.' => Ok(c),
+        _ => Err("`.`, `#` or a single `^`"),
+    })?;
 
-    fn next_coordinates(&self, (x, y): (usize, usize)) -> (usize, usize) {
-        match self {
-            Self::North => (x - 1, y),
-            Self::East => (x, y + 1),
-            Self::South => (x + 1, y),
-            Self::West => (x, y - 1),
-        }
-    }
+    let start_position = start_position.ok_or_else(|| ParseError::end(input, "`^`"))?;
 
-    fn to_bitmask(self) -> u8 {
-        match self {
-            Self::North => 1 << 0,
-            Self::East => 1 << 1,
-            Self::South => 1 << 2,
-            Self::West => 1 << 3,
-        }
-    }
+    Ok((grid, start_position))
 }
 
-fn parse(input: &str) -> Input {
-    let mut grid = [[b'%'; INPUT_SIZE + 2]; INPUT_SIZE + 2];
-    let mut start_position = None;
-
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.bytes().enumerate().for_each(|(x, c)| match c {
-            b'^' => {
-                start_position = Some((y + 1, x + 1));
-                grid[y + 1][x + 1] = Direction::North.to_bitmask();
-            }
-            b'#' => grid[y + 1][x + 1] = c,
-            _ => grid[y + 1][x + 1] = 0,
-        });
-    });
-
-    (grid, start_position.unwrap())
+pub fn part1(input: &str) -> Result<u32, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part1(input: &str) -> u32 {
-    let (mut grid, (mut x, mut y)) = parse(input);
-    let mut visited = 1;
+/// Number of positions visited before leaving the grid, `None` when the guard never leaves
+pub fn part1_solve((grid, mut position): Input) -> Option<u32> {
+    let mut visited = Grid::new(grid.height(), grid.width(), DirectionSet::default());
     let mut direction = Direction::North;
+    visited[position].insert(direction);
+    let mut visited_count = 1;
 
-    loop {
-        let (next_x, next_y) = direction.next_coordinates((x, y));
-
-        match grid[next_x][next_y] {
-            b'%' => break,
+    // Until the guard leaves the grid
+    while let Some(next) = grid.step(position, direction) {
+        match grid[next] {
             b'#' => {
                 // We hit a wall, just change direction
-                direction = direction.next_direction();
-            }
-            0 => {
-                // We can move forward, increase visited count
-                x = next_x;
-                y = next_y;
-                grid[x][y] = b'X';
-                visited += 1;
+                direction = direction.turn_right();
             }
             _ => {
-                // We can move forward
-                x = next_x;
-                y = next_y;
+                position = next;
+                if visited[position].is_empty() {
+                    visited_count += 1;
+                }
             }
         }
+
+        // Turns are recorded too, for a guard boxed in by walls
+        if !visited[position].insert(direction) {
+            return None;
+        }
     }
 
-    visited
+    Some(visited_count)
 }
 
-pub fn part2(input: &str) -> usize {
-    let (mut grid, (mut x, mut y)) = parse(input);
-    let mut cycles = 0;
-    let mut direction = Direction::North;
-
-    loop {
-        let (next_x, next_y) = direction.next_coordinates((x, y));
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-        match grid[next_x][next_y] {
-            b'%' => break,
+/// Number of positions where an obstacle makes the guard walk in circles, `None` when the guard
+/// already never leaves the grid
+pub fn part2_solve((grid, mut position): Input) -> Option<usize> {
+    let mut visited = Grid::new(grid.height(), grid.width(), DirectionSet::default());
+    let mut direction = Direction::North;
+    visited[position].insert(direction);
+    // An obstacle can only be placed where the guard goes for the first time, the guard is then
+    // next to it, facing it
+    let mut candidates = Vec::new();
+
+    // Until the guard leaves the grid
+    while let Some(next) = grid.step(position, direction) {
+        match grid[next] {
             b'#' => {
                 // We hit a wall, just change direction
-                direction = direction.next_direction();
-                grid[x][y] |= direction.to_bitmask();
-            }
-            0 => {
-                let mut cycle_direction = direction.next_direction();
-                let mut cycle_grid = grid;
-                cycle_grid[next_x][next_y] = b'#';
-                let (mut cycle_x, mut cycle_y) = (x, y);
-
-                loop {
-                    let (next_cycle_x, next_cycle_y) =
-                        cycle_direction.next_coordinates((cycle_x, cycle_y));
-
-                    match cycle_grid[next_cycle_x][next_cycle_y] {
-                        b'%' => break,
-                        b'#' => {
-                            cycle_direction = cycle_direction.next_direction();
-                        }
-                        cycle_cell => {
-                            if cycle_cell & cycle_direction.to_bitmask() != 0 {
-                                cycles += 1;
-                                break;
-                            } else {
-                                cycle_x = next_cycle_x;
-                                cycle_y = next_cycle_y;
-                                cycle_grid[cycle_x][cycle_y] |= cycle_direction.to_bitmask();
-                            }
-                        }
-                    }
-                }
-
-                x = next_x;
-                y = next_y;
-                grid[x][y] |= direction.to_bitmask();
+                direction = direction.turn_right();
             }
             _ => {
-                x = next_x;
-                y = next_y;
-                grid[x][y] |= direction.to_bitmask();
+                if visited[next].is_empty() {
+                    candidates.push((next, position, direction));
+                }
+                position = next;
             }
         }
+
+        if !visited[position].insert(direction) {
+            return None;
+        }
     }
 
-    cycles
+    // Each worker reuses its visited grid across its candidates
+    let (loops, _) = par_fold(
+        &candidates,
+        || (0, Visited::new(&grid)),
+        |(loops, mut visited), &(obstacle, position, direction)| {
+            let _span = debug_span!("loop_detection").entered();
+            let is_loop = is_loop(&grid, obstacle, position, direction, &mut visited);
+            (loops + usize::from(is_loop), visited)
+        },
+        |(a, visited), (b, _)| (a + b, visited),
+    );
+
+    Some(loops)
+}
+
+/// Directions the guard faced on each position, with the positions to reset for the next walk
+struct Visited {
+    directions: Grid<DirectionSet>,
+    touched: Vec<(usize, usize)>,
+}
+
+impl Visited {
+    fn new(grid: &Grid<u8>) -> Self {
+        Self {
+            directions: Grid::new(grid.height(), grid.width(), DirectionSet::default()),
+            touched: Vec::new(),
+        }
+    }
+
+    /// Record the guard on `position` facing `direction`, returning whether it is new
+    fn insert(&mut self, position: (usize, usize), direction: Direction) -> bool {
+        let directions = &mut self.directions[position];
+        if directions.is_empty() {
+            self.touched.push(position);
+        }
+        directions.insert(direction)
+    }
+
+    fn clear(&mut self) {
+        for position in self.touched.drain(..) {
+            self.directions[position] = DirectionSet::default();
+        }
+    }
+}
+
+/// Whether the guard walks in circles from `position`, facing `direction`, once `obstacle` is
+/// added to the grid
+fn is_loop(
+    grid: &Grid<u8>,
+    obstacle: (usize, usize),
+    mut position: (usize, usize),
+    mut direction: Direction,
+    visited: &mut Visited,
+) -> bool {
+    visited.clear();
+
+    loop {
+        let Some(next) = grid.step(position, direction) else {
+            return false;
+        };
+
+        match grid[next] {
+            b'#' => direction = direction.turn_right(),
+            _ if next == obstacle => direction = direction.turn_right(),
+            _ => position = next,
+        }
+
+        // Turns are recorded too, for a guard boxed in by the obstacle
+        if !visited.insert(position, direction) {
+            return true;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -165,17 +191,27 @@ mod tests {
 
 ";
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 41);
+    crate::utils::day_tests! {
+        day: 06,
+        examples: {
+            part1(EXAMPLE) => 41,
+        },
+        input: {
+            part1 => 5162,
+            part2 => 1909,
+        },
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 5162);
+    fn malformed() {
+        assert_eq!(
+            part1("....\n.#..\n").unwrap_err().to_string(),
+            "line 3 col 1: expected `^`"
+        );
+        assert_eq!(
+            part2("..^.\n.#x.\n").unwrap_err().to_string(),
+            "line 2 col 3: expected `.`, `#` or a single `^`"
+        );
     }
 
     #[test]
@@ -187,7 +223,7 @@ mod tests {
 ^..#.
 ";
 
-        assert_eq!(part2(test_case), 1);
+        assert_eq!(part2(test_case), Ok(1));
 
         let test_case = "\
 .....
@@ -196,7 +232,7 @@ mod tests {
 .....
 ";
 
-        assert_eq!(part2(test_case), 0);
+        assert_eq!(part2(test_case), Ok(0));
 
         let test_case = "\
 .....
@@ -205,16 +241,22 @@ mod tests {
 .^.#.
 ";
 
-        assert_eq!(part2(test_case), 1);
+        assert_eq!(part2(test_case), Ok(1));
 
-        assert_eq!(part2(EXAMPLE), 6);
+        assert_eq!(part2(EXAMPLE), Ok(6));
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 1909);
+    fn never_leaves() {
+        let boxed_in = ".#.\n#^#\n.#.\n";
+        assert_eq!(part1(boxed_in), Err(SolveError::NoAnswer));
+        assert_eq!(part2(boxed_in), Err(SolveError::NoAnswer));
+
+        let circles = ".#..\n...#\n#^..\n..#.\n";
+        assert_eq!(part1(circles), Err(SolveError::NoAnswer));
+        assert_eq!(part2(circles), Err(SolveError::NoAnswer));
+
+        // The obstacle completes the box
+        assert_eq!(part2(".#.\n#^#\n...\n"), Ok(1));
     }
 }
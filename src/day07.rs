@@ -1,7 +1,6 @@
-use crate::utils::number_of_digits_u64;
+use crate::utils::{integers, number_of_digits_u64, par_sum, ParseError};
 use std::collections::VecDeque;
-
-const SIZE: usize = 12;
+use tracing::info_span;
 
 #[derive(Debug)]
 enum Operations {
@@ -18,64 +17,89 @@ impl std::fmt::Display for Operations {
     }
 }
 
-fn parse(input: &str) -> Vec<(u64, Vec<u32>)> {
+/// Equations as their test value and numbers
+pub fn parse(input: &str) -> Result<Vec<(u64, Vec<u64>)>, ParseError> {
     input
         .trim()
         .lines()
         .map(|line| {
-            let (target, numbers) = line.split_once(": ").unwrap();
+            let mut numbers = integers(input, line).collect::<Result<Vec<_>, _>>()?;
+            if numbers.len() < 2 {
+                let expected = "a test value followed by `: ` and numbers";
+                return Err(ParseError::at(input, line, expected));
+            }
+            let target = numbers.remove(0);
 
-            (
-                target.parse().unwrap(),
-                numbers.split(' ').map(|n| n.parse().unwrap()).collect(),
-            )
+            Ok((target, numbers))
         })
         .collect()
 }
 
-pub fn part1(input: &str) -> u64 {
-    let input = parse(input);
+/// Sums of `u64` test values, which overflow a `u64`
+type Num = u128;
 
-    input
-        .into_iter()
-        .map(|(target, numbers)| {
-            // Content is (operation, current_total, current_index)
-            let mut operations_queue = VecDeque::with_capacity(4 * numbers.len());
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
+}
+
+pub fn part1_solve(input: Vec<(u64, Vec<u64>)>) -> Num {
+    par_sum(&input, |&(target, ref numbers)| {
+        if numbers.len() == 1 {
+            // An equation of a single number has no operator
+            return if numbers[0] == target {
+                target.into()
+            } else {
+                0
+            };
+        }
 
-            operations_queue.push_back((Operations::Add, numbers[0] as u64, 0));
-            operations_queue.push_back((Operations::Multiply, numbers[0] as u64, 0));
+        // Content is (operation, current_total, current_index)
+        let mut operations_queue = VecDeque::with_capacity(4 * numbers.len());
 
-            while !operations_queue.is_empty() {
-                let (operation, current_value, current_index) =
-                    operations_queue.pop_back().unwrap();
+        operations_queue.push_back((Operations::Add, numbers[0], 0));
+        operations_queue.push_back((Operations::Multiply, numbers[0], 0));
 
-                let next_index = current_index + 1;
+        while !operations_queue.is_empty() {
+            let (operation, current_value, current_index) = operations_queue.pop_back().unwrap();
 
-                if next_index == numbers.len() {
-                    // We reached the end, it's joever for this branch
-                    continue;
-                }
+            let next_index = current_index + 1;
 
-                let next_number = numbers[next_index] as u64;
+            if next_index == numbers.len() {
+                // We reached the end, it's joever for this branch
+                continue;
+            }
 
-                let new_value = match operation {
-                    Operations::Add => current_value + next_number,
-                    Operations::Multiply => current_value * next_number,
-                };
+            let next_number = numbers[next_index];
 
-                if next_index == numbers.len() - 1 && new_value == target {
-                    return target;
-                }
+            // Values that overflow are above the target
+            let Some(new_value) = (match operation {
+                Operations::Add => current_value.checked_add(next_number),
+                Operations::Multiply => current_value.checked_mul(next_number),
+            }) else {
+                continue;
+            };
 
-                if new_value <= target {
-                    operations_queue.push_back((Operations::Add, new_value, next_index));
-                    operations_queue.push_back((Operations::Multiply, new_value, next_index));
-                }
+            if next_index == numbers.len() - 1 && new_value == target {
+                return target.into();
             }
 
-            0
-        })
-        .sum()
+            if new_value <= target {
+                operations_queue.push_back((Operations::Add, new_value, next_index));
+                operations_queue.push_back((Operations::Multiply, new_value, next_index));
+            }
+        }
+
+        0
+    })
+}
+
+/// `current` followed by the digits of `value`, `None` when it overflows
+fn concatenate(current: u64, value: u64) -> Option<u64> {
+    10_u64
+        .checked_pow(number_of_digits_u64(value))
+        .and_then(|shift| current.checked_mul(shift))
+        .and_then(|shifted| shifted.checked_add(value))
 }
 
 #[inline(always)]
@@ -83,38 +107,40 @@ fn is_solvable_2(target: u64, current: u64, values: &[u64]) -> bool {
     if current > target {
         return false;
     }
-    if values.len() == 1 {
-        return current + values[0] == target
-            || current * values[0] == target
-            || current * 10_u64.pow(number_of_digits_u64(values[0])) + values[0] == target;
+
+    let Some((&value, values)) = values.split_first() else {
+        // An equation of a single number has no operator
+        return current == target;
+    };
+
+    // Values that overflow are above the target
+    if values.is_empty() {
+        return current.checked_add(value) == Some(target)
+            || current.checked_mul(value) == Some(target)
+            || concatenate(current, value) == Some(target);
     }
 
-    is_solvable_2(target, current + values[0], &values[1..])
-        || is_solvable_2(target, current * values[0], &values[1..])
-        || is_solvable_2(
-            target,
-            current * 10_u64.pow(number_of_digits_u64(values[0])) + values[0],
-            &values[1..],
-        )
+    current
+        .checked_add(value)
+        .is_some_and(|next| is_solvable_2(target, next, values))
+        || current
+            .checked_mul(value)
+            .is_some_and(|next| is_solvable_2(target, next, values))
+        || concatenate(current, value).is_some_and(|next| is_solvable_2(target, next, values))
 }
 
-pub fn part2(input: &str) -> u64 {
-    input
-        .lines()
-        .map(|l| {
-            let (total, values_str) = l.split_once(": ").unwrap();
-            let total: u64 = total.parse().unwrap();
-            let mut values: Vec<u64> = Vec::with_capacity(SIZE);
-            values_str
-                .split_whitespace()
-                .for_each(|v| values.push(v.parse().unwrap()));
-
-            if is_solvable_2(total, values[0], &values[1..]) {
-                return total;
-            }
-            0
-        })
-        .sum()
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(parsed)))
+}
+
+pub fn part2_solve(input: Vec<(u64, Vec<u64>)>) -> Num {
+    par_sum(&input, |(total, values)| {
+        if is_solvable_2(*total, values[0], &values[1..]) {
+            return Num::from(*total);
+        }
+        0
+    })
 }
 
 #[cfg(test)]
@@ -134,10 +160,16 @@ mod tests {
     "
     };
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 3749);
-        assert_eq!(part2(EXAMPLE), 11387);
+    crate::utils::day_tests! {
+        day: 07,
+        examples: {
+            part1(EXAMPLE) => 3749,
+            part2(EXAMPLE) => 11387,
+        },
+        input: {
+            part1 => 3312271365652,
+            part2 => 509463489296712,
+        },
     }
 
     #[test]
@@ -147,22 +179,30 @@ mod tests {
 6148: 6 95 376 8 9 58 6 16 6 1
 ";
 
-        assert_eq!(part1(sample), 6148);
+        assert_eq!(part1(sample), Ok(6148));
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 3312271365652);
+    fn malformed() {
+        assert_eq!(
+            part1("190: 10 19\n83:\n").unwrap_err().to_string(),
+            "line 2 col 1: expected a test value followed by `: ` and numbers"
+        );
+        assert_eq!(
+            part2("190: 10 19\n83: 17 -5\n").unwrap_err().to_string(),
+            "line 2 col 8: expected a number"
+        );
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 509463489296712);
+    fn edge_cases() {
+        let sample = "7: 7\n8: 7\n";
+        assert_eq!(part1(sample), Ok(7));
+        assert_eq!(part2(sample), Ok(7));
+
+        // Test values overflow their sum, and operations overflow the numbers
+        let sample = "18446744073709551615: 18446744073709551615\n18446744073709551614: 9223372036854775807 2\n5: 18446744073709551615 18446744073709551615 5\n";
+        assert_eq!(part1(sample), Ok(2 * u64::MAX as Num - 1));
+        assert_eq!(part2(sample), Ok(2 * u64::MAX as Num - 1));
     }
 }
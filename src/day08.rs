@@ -1,81 +1,87 @@
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
+use tracing::info_span;
 
-type Num = usize;
+use crate::utils::{grid_dimensions, ParseError, Point};
 
-#[derive(Clone, Eq, Hash, PartialEq, Debug)]
-struct P {
-    x: isize,
-    y: isize,
-}
+type Num = usize;
 
-fn parse(input: &str) -> (HashMap<u8, Vec<P>>, isize) {
-    let mut antenas = HashMap::<u8, Vec<P>>::new();
-    input.trim().lines().enumerate().for_each(|(x, l)| {
-        l.bytes().enumerate().for_each(|(y, b)| match b {
-            b'.' => (),
-            c => {
-                antenas.entry(c).or_default().push(P {
-                    x: x as isize,
-                    y: y as isize,
-                });
+/// Antennas per frequency, and the height and width of the map
+type Input = (HashMap<u8, Vec<Point>>, (usize, usize));
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let (height, width) = grid_dimensions(input)?;
+    let mut antenas = HashMap::<u8, Vec<Point>>::new();
+    for (x, l) in input.lines().take(height).enumerate() {
+        for (y, b) in l.bytes().enumerate() {
+            match b {
+                b'.' => (),
+                c if c.is_ascii_alphanumeric() => {
+                    let antenna = Point::try_from((x, y)).expect("the grid size is capped");
+                    antenas.entry(c).or_default().push(antenna);
+                }
+                _ => {
+                    return Err(ParseError::at(
+                        input,
+                        &l[y..],
+                        "`.` or an antenna frequency",
+                    ))
+                }
             }
-        })
-    });
+        }
+    }
 
-    (antenas, input.lines().count() as isize)
+    Ok((antenas, (height, width)))
 }
 
-fn add_in_bound(lhs: (isize, isize), rhs: (isize, isize), bound: isize) -> Option<P> {
-    let (x, y) = (lhs.0 + rhs.0, lhs.1 + rhs.1);
-
-    (x >= 0 && y >= 0 && x < bound && y < bound).then_some(P { x, y })
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
 }
 
-pub fn part1(input: &str) -> Num {
-    let (antenas, max_size) = parse(input);
-    let mut antinodes = HashSet::<P>::new();
+pub fn part1_solve((antenas, dimensions): Input) -> Num {
+    let mut antinodes = HashSet::<Point>::new();
 
     antenas.into_iter().for_each(|(_, v)| {
         v.into_iter().permutations(2).for_each(|p| {
-            let P { x: x0, y: y0 } = p[0];
-            let P { x: x1, y: y1 } = p[1];
+            let delta = p[1] - p[0];
 
-            let (dx, dy) = (x1 - x0, y1 - y0);
-
-            add_in_bound((x1, y1), (dx, dy), max_size).map(|p| antinodes.insert(p));
-            add_in_bound((x0, y0), (-dx, -dy), max_size).map(|p| antinodes.insert(p));
+            if let Some(antinode) = p[1].bounded_add(delta, dimensions) {
+                antinodes.insert(antinode);
+            }
+            if let Some(antinode) = p[0].bounded_add(-delta, dimensions) {
+                antinodes.insert(antinode);
+            }
         });
     });
 
     antinodes.len()
 }
 
-pub fn part2(input: &str) -> Num {
-    let (antenas, max_size) = parse(input);
-    let mut antinodes = HashSet::<P>::new();
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(parsed)))
+}
+
+pub fn part2_solve((antenas, dimensions): Input) -> Num {
+    let mut antinodes = HashSet::<Point>::new();
 
     antenas.into_iter().for_each(|(_, v)| {
         v.into_iter().permutations(2).for_each(|p| {
-            let P { x: x0, y: y0 } = p[0];
-            let P { x: x1, y: y1 } = p[1];
-
-            let (dx, dy) = (x1 - x0, y1 - y0);
+            let delta = p[1] - p[0];
 
-            let (mut x, mut y) = (x1, y1);
-            antinodes.insert(P { x, y });
-            while let Some(p) = add_in_bound((x, y), (dx, dy), max_size) {
-                x = p.x;
-                y = p.y;
-                antinodes.insert(p);
+            let mut antinode = p[1];
+            antinodes.insert(antinode);
+            while let Some(next) = antinode.bounded_add(delta, dimensions) {
+                antinode = next;
+                antinodes.insert(antinode);
             }
-            let (mut x, mut y) = (x0, y0);
-            antinodes.insert(P { x, y });
-            while let Some(p) = add_in_bound((x, y), (-dx, -dy), max_size) {
-                x = p.x;
-                y = p.y;
-                antinodes.insert(p);
+            let mut antinode = p[0];
+            antinodes.insert(antinode);
+            while let Some(next) = antinode.bounded_add(-delta, dimensions) {
+                antinode = next;
+                antinodes.insert(antinode);
             }
         });
     });
@@ -104,25 +110,15 @@ mod tests {
         "
     };
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 14);
-        assert_eq!(part2(EXAMPLE), 34);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 295);
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 1034);
+    crate::utils::day_tests! {
+        day: 08,
+        examples: {
+            part1(EXAMPLE) => 14,
+            part2(EXAMPLE) => 34,
+        },
+        input: {
+            part1 => 295,
+            part2 => 1034,
+        },
     }
 }
@@ -1,16 +1,38 @@
+use tracing::info_span;
+
+use crate::utils::ParseError;
+
 type Num = usize;
 
-pub fn part1(input: &str) -> Num {
-    let line = input.trim().lines().next().unwrap();
+/// Digits of the disk map, on the first line
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    let line = input
+        .trim()
+        .lines()
+        .next()
+        .ok_or_else(|| ParseError::end(input, "a disk map"))?;
 
-    let input_size = line.len();
+    line.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .ok_or_else(|| ParseError::at(input, &line[i..], "a digit"))
+        })
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let digits = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(digits)))
+}
+
+pub fn part1_solve(digits: Vec<u32>) -> Num {
+    let input_size = digits.len();
 
     // Id if file is its index *2
     let mut file_sizes = Vec::with_capacity(input_size / 2);
     let mut empty_space_sizes = Vec::with_capacity(input_size / 2);
 
-    line.chars().enumerate().for_each(|(i, b)| {
-        let b = b.to_digit(10).unwrap();
+    digits.into_iter().enumerate().for_each(|(i, b)| {
         match i & 1 {
             0 => {
                 // This is a file
@@ -56,21 +78,21 @@ pub fn part1(input: &str) -> Num {
             counting_index += 1;
         }
 
-        // 2. Fill the empty space with the current rightmost file
-        for _ in 0..empty_space_sizes[index] {
+        // 2. Fill the empty space with the current rightmost file, the last file has none
+        for _ in 0..empty_space_sizes.get(index).copied().unwrap_or(0) {
             match right_file_state.leftover {
                 0 => {
-                    // We have no ongoing file to copy, find the next one
+                    // We have no ongoing file to copy, find the next one, skipping empty files
                     right_file_state.index -= 1;
-                    if right_file_state.index + 1 == index + 1 {
+                    while right_file_state.index > index && file_sizes[right_file_state.index] == 0
+                    {
+                        right_file_state.index -= 1;
+                    }
+                    if right_file_state.index == index {
                         // We have no more files to copy
                         break;
                     }
                     let file = file_sizes[right_file_state.index];
-                    if file == 0 {
-                        // Skip empty files
-                        panic!("MANAGE EMPTY FILES");
-                    }
                     sum += counting_index * (right_file_state.index);
                     right_file_state.leftover = file - 1;
                     counting_index += 1;
@@ -90,10 +112,13 @@ pub fn part1(input: &str) -> Num {
     sum
 }
 
-pub fn part2(input: &str) -> Num {
-    let line = input.trim().lines().next().unwrap();
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let digits = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(digits)))
+}
 
-    let input_size = line.len();
+pub fn part2_solve(digits: Vec<u32>) -> Num {
+    let input_size = digits.len();
 
     // Id if file is its index *2
     #[derive(Debug)]
@@ -110,9 +135,10 @@ pub fn part2(input: &str) -> Num {
     let mut file_sizes = Vec::with_capacity(input_size / 2);
     let mut empty_spaces = Vec::with_capacity(input_size / 2);
 
-    line.chars().enumerate().for_each(|(i, b)| {
-        let b = b.to_digit(10).unwrap();
-        match i & 1 {
+    digits
+        .into_iter()
+        .enumerate()
+        .for_each(|(i, b)| match i & 1 {
             0 => {
                 file_sizes.push(File {
                     size: b as usize,
@@ -127,8 +153,15 @@ pub fn part2(input: &str) -> Num {
                 });
             }
             _ => unreachable!(),
+        });
+
+    // Empty files do not split the free space around them, merge it on their left. Going from
+    // right to left merges runs of empty files too
+    for i in (1..empty_spaces.len()).rev() {
+        if file_sizes[i].size == 0 {
+            empty_spaces[i - 1].size += std::mem::take(&mut empty_spaces[i].size);
         }
-    });
+    }
 
     // Go through all files from right to left
     // For each file, find the leftmost empty space that fits the file
@@ -183,15 +216,22 @@ pub fn part2(input: &str) -> Num {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc::indoc! {"
         2333133121414131402
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 1928);
-        assert_eq!(part2(EXAMPLE), 2858);
+    crate::utils::day_tests! {
+        day: 09,
+        examples: {
+            part1(EXAMPLE) => 1928,
+            part2(EXAMPLE) => 2858,
+        },
+        input: {
+            part1 => 6471961544878,
+            part2 => 6511178035564,
+        },
     }
 
     #[test]
@@ -199,22 +239,93 @@ mod tests {
         let sample = indoc::indoc! {"
           12345
 "};
-        assert_eq!(part1(sample), 60);
+        assert_eq!(part1(sample), Ok(60));
+
+        // Empty files have no block, but they still take an id
+        assert_eq!(part1("13200"), Ok(1 + 2));
+        assert_eq!(part2("13200"), Ok(1 + 2));
+        assert_eq!(part1("12031"), Ok(2));
+        assert_eq!(part2("12031"), Ok(2));
     }
 
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 6471961544878);
+    /// Blocks of the disk, with the id of their file
+    fn blocks(digits: &[u32]) -> Vec<Option<usize>> {
+        digits
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &size)| {
+                std::iter::repeat_n((i % 2 == 0).then_some(i / 2), size as usize)
+            })
+            .collect()
     }
 
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 6511178035564);
+    fn checksum(blocks: &[Option<usize>]) -> usize {
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(position, id)| position * id.unwrap_or(0))
+            .sum()
+    }
+
+    /// Move the last block to the first free space, one block at a time
+    fn compact_blocks(digits: &[u32]) -> usize {
+        let mut blocks = blocks(digits);
+        let (mut free, mut last) = (0, blocks.len() - 1);
+        loop {
+            while free < blocks.len() && blocks[free].is_some() {
+                free += 1;
+            }
+            while blocks[last].is_none() {
+                last -= 1;
+            }
+            if free >= last {
+                return checksum(&blocks);
+            }
+            blocks.swap(free, last);
+        }
+    }
+
+    /// Move each file once, by decreasing id, to the first free span on its left large enough
+    fn compact_files(digits: &[u32]) -> usize {
+        let mut blocks = blocks(digits);
+        for id in (0..digits.len().div_ceil(2)).rev() {
+            let size = digits[2 * id] as usize;
+            if size == 0 {
+                continue;
+            }
+            let start = blocks.iter().position(|&block| block == Some(id)).unwrap();
+            if let Some(free) = blocks[..start]
+                .windows(size)
+                .position(|span| span.iter().all(Option::is_none))
+            {
+                for i in 0..size {
+                    blocks.swap(free + i, start + i);
+                }
+            }
+        }
+        checksum(&blocks)
+    }
+
+    /// Disk maps start and end with a file, only the first file is never empty
+    fn disk_map() -> impl Strategy<Value = Vec<u32>> {
+        (
+            1..=9_u32,
+            prop::collection::vec((0..=9_u32, 0..=9_u32), 0..20),
+        )
+            .prop_map(|(first, pairs)| {
+                std::iter::once(first)
+                    .chain(pairs.into_iter().flat_map(|(free, file)| [free, file]))
+                    .collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn block_simulation(digits in disk_map()) {
+            let input = digits.iter().map(|d| d.to_string()).collect::<String>();
+
+            prop_assert_eq!(part1(&input), Ok(compact_blocks(&digits)));
+            prop_assert_eq!(part2(&input), Ok(compact_files(&digits)));
+        }
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
+use tracing::info_span;
 
-type Num = usize;
+use crate::utils::{grid_dimensions, AdjacentPositions, ParseError};
 
-const INPUT_SIZE: usize = 55;
+type Num = usize;
 
 #[derive(Clone, Debug)]
 struct Cell {
@@ -10,26 +11,52 @@ struct Cell {
     reachable_summits: HashSet<(usize, usize)>,
 }
 
-pub fn part1(input: &str) -> Num {
-    let mut grid: [[Cell; INPUT_SIZE + 2]; INPUT_SIZE + 2] = core::array::from_fn(|_| {
-        core::array::from_fn(|_| Cell {
-            height: u8::MAX - 1,
-            reachable_summits: HashSet::new(),
-        })
-    });
+/// Impassable height, of the border and of the characters other than digits
+const IMPASSABLE: u8 = u8::MAX - 1;
 
-    input.lines().enumerate().for_each(|(x, line)| {
+/// Heights of the map surrounded by an impassable border
+pub fn parse(input: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    // Characters other than digits are impassable, only the size of the map can be wrong
+    let (rows, columns) = grid_dimensions(input)?;
+    let mut grid = vec![vec![IMPASSABLE; columns + 2]; rows + 2];
+
+    input.lines().take(rows).enumerate().for_each(|(x, line)| {
         line.chars().enumerate().for_each(|(y, c)| {
-            let cell = &mut grid[x + 1][y + 1];
-            cell.height = c.to_digit(10).unwrap_or((u8::MAX - 1).into()) as u8;
-            if cell.height == 9 {
-                cell.reachable_summits.insert((x + 1, y + 1));
-            }
+            grid[x + 1][y + 1] = c.to_digit(10).unwrap_or(IMPASSABLE.into()) as u8;
         });
     });
 
-    for x in 1..INPUT_SIZE + 1 {
-        for y in 1..INPUT_SIZE + 1 {
+    Ok(grid)
+}
+
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let heights = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(heights)))
+}
+
+pub fn part1_solve(heights: Vec<Vec<u8>>) -> Num {
+    let (rows, columns) = (heights.len() - 2, heights[0].len() - 2);
+    let bound = (heights.len(), heights[0].len());
+    let mut grid = heights
+        .into_iter()
+        .enumerate()
+        .map(|(x, row)| {
+            row.into_iter()
+                .enumerate()
+                .map(|(y, height)| Cell {
+                    height,
+                    reachable_summits: if height == 9 {
+                        HashSet::from([(x, y)])
+                    } else {
+                        HashSet::new()
+                    },
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for x in 1..rows + 1 {
+        for y in 1..columns + 1 {
             if grid[x][y].height != 9 {
                 continue;
             }
@@ -46,21 +73,16 @@ pub fn part1(input: &str) -> Num {
                     continue;
                 }
 
-                let directions: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-
                 let current_height = current.height;
 
-                directions.iter().for_each(|(dx, dy)| {
-                    let (n_x, n_y) = (
-                        path_cell_x.checked_add_signed(*dx).unwrap(),
-                        path_cell_y.checked_add_signed(*dy).unwrap(),
-                    );
-
-                    if grid[n_x][n_y].height == current_height - 1 {
-                        grid[n_x][n_y].reachable_summits.insert((x, y));
-                        queue.push((n_x, n_y));
-                    }
-                });
+                (path_cell_x, path_cell_y)
+                    .cardinal_positions(bound)
+                    .for_each(|(n_x, n_y)| {
+                        if grid[n_x][n_y].height == current_height - 1 {
+                            grid[n_x][n_y].reachable_summits.insert((x, y));
+                            queue.push((n_x, n_y));
+                        }
+                    });
             }
         }
     }
@@ -84,24 +106,28 @@ struct Cell2 {
     reachable_summits_count: u32,
 }
 
-pub fn part2(input: &str) -> Num {
-    let mut grid = [[Cell2 {
-        height: u8::MAX - 1,
-        reachable_summits_count: 0,
-    }; INPUT_SIZE + 2]; INPUT_SIZE + 2];
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let heights = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(heights)))
+}
 
-    input.lines().enumerate().for_each(|(x, line)| {
-        line.chars().enumerate().for_each(|(y, c)| {
-            let cell = &mut grid[x + 1][y + 1];
-            cell.height = c.to_digit(10).unwrap_or((u8::MAX - 1).into()) as u8;
-            if cell.height == 9 {
-                cell.reachable_summits_count = 1;
-            }
-        });
-    });
+pub fn part2_solve(heights: Vec<Vec<u8>>) -> Num {
+    let (rows, columns) = (heights.len() - 2, heights[0].len() - 2);
+    let bound = (heights.len(), heights[0].len());
+    let mut grid = heights
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|height| Cell2 {
+                    height,
+                    reachable_summits_count: (height == 9).into(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
 
-    for x in 1..INPUT_SIZE + 1 {
-        for y in 1..INPUT_SIZE + 1 {
+    for x in 1..rows + 1 {
+        for y in 1..columns + 1 {
             if grid[x][y].height != 9 {
                 continue;
             }
@@ -118,14 +144,7 @@ pub fn part2(input: &str) -> Num {
                     continue;
                 }
 
-                let directions: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-
-                directions.iter().for_each(|(dx, dy)| {
-                    let (x, y) = (
-                        x.checked_add_signed(*dx).unwrap(),
-                        y.checked_add_signed(*dy).unwrap(),
-                    );
-
+                (x, y).cardinal_positions(bound).for_each(|(x, y)| {
                     if grid[x][y].height == current.height - 1 {
                         grid[x][y].reachable_summits_count += 1;
                         queue.push((x, y));
@@ -162,10 +181,16 @@ mod tests {
         10456732
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 36);
-        assert_eq!(part2(EXAMPLE), 81);
+    crate::utils::day_tests! {
+        day: 10,
+        examples: {
+            part1(EXAMPLE) => 36,
+            part2(EXAMPLE) => 81,
+        },
+        input: {
+            part1 => 624,
+            part2 => 1483,
+        },
     }
 
     #[test]
@@ -180,22 +205,6 @@ mod tests {
             9.....9
 "};
 
-        assert_eq!(part1(sample), 2);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 624);
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 1483);
+        assert_eq!(part1(sample), Ok(2));
     }
 }
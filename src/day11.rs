@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use tracing::info_span;
 
-use crate::utils::number_of_digits_u64;
+use crate::{
+    utils::{number_of_digits_u64, parse_number, Memo, ParseError},
+    SolveError,
+};
 
 type Num = u64;
 
-fn count_steps(stone: Num, steps: usize) -> Num {
+/// Stones after `steps` blinks, `None` when an engraved number overflows
+fn count_steps(stone: Num, steps: usize) -> Option<Num> {
     if steps == 0 {
-        return 1;
+        return Some(1);
     }
 
     if stone == 0 {
@@ -23,113 +27,153 @@ fn count_steps(stone: Num, steps: usize) -> Num {
             let left = stone / divisor;
             let right = stone % divisor;
 
-            count_steps(left, steps - 1) + count_steps(right, steps - 1)
+            count_steps(left, steps - 1)?.checked_add(count_steps(right, steps - 1)?)
         }
         1 => {
-            let next_stone = stone * 2024;
+            let next_stone = stone.checked_mul(2024)?;
             count_steps(next_stone, steps - 1)
         }
         _ => unreachable!(),
     }
 }
 
-pub fn part1(input: &str) -> Num {
+/// Numbers engraved on the stones
+pub fn parse(input: &str) -> Result<Vec<Num>, ParseError> {
     input
         .lines()
         .next()
-        .unwrap()
+        .ok_or_else(|| ParseError::end(input, "a line of stones"))?
         .split(" ")
-        .map(|n_str| {
-            let n = n_str.parse().unwrap();
+        .map(|n_str| parse_number(input, n_str))
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<Num, SolveError> {
+    let stones = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(stones))
+        .ok_or(SolveError::NoAnswer)
+}
 
-            count_steps(n, 25)
-        })
-        .sum()
+pub fn part1_solve(stones: Vec<Num>) -> Option<Num> {
+    stones
+        .into_iter()
+        .try_fold(0, |total: Num, n| total.checked_add(count_steps(n, 25)?))
 }
 
-fn count_steps_cached(stone: Num, steps: u32, cache: &mut HashMap<(Num, u32), Num>) -> Num {
+fn count_steps_cached(
+    stone: Num,
+    steps: u32,
+    cache: &mut Memo<(Num, u32), Option<Num>>,
+) -> Option<Num> {
     if steps == 0 {
-        return 1;
+        return Some(1);
     }
 
     if stone == 0 {
         return count_steps_cached(1, steps - 1, cache);
     }
 
-    if let Some(&count) = cache.get(&(stone, steps)) {
-        return count;
-    }
-
-    let total_digits = number_of_digits_u64(stone);
-    match total_digits & 1 {
-        0 => {
-            let half_digits = total_digits / 2;
-
-            let divisor = 10u64.pow(half_digits);
-
-            let left = stone / divisor;
-            let right = stone % divisor;
-
-            let count = count_steps_cached(left, steps - 1, cache)
-                + count_steps_cached(right, steps - 1, cache);
-
-            cache.insert((stone, steps), count);
-
-            count
+    cache.get_or_compute((stone, steps), |cache| {
+        let total_digits = number_of_digits_u64(stone);
+        match total_digits & 1 {
+            0 => {
+                let half_digits = total_digits / 2;
+
+                let divisor = 10u64.pow(half_digits);
+
+                let left = stone / divisor;
+                let right = stone % divisor;
+
+                count_steps_cached(left, steps - 1, cache)?.checked_add(count_steps_cached(
+                    right,
+                    steps - 1,
+                    cache,
+                )?)
+            }
+            1 => {
+                let next_stone = stone.checked_mul(2024)?;
+                count_steps_cached(next_stone, steps - 1, cache)
+            }
+            _ => unreachable!(),
         }
-        1 => {
-            let next_stone = stone * 2024;
-            let count = count_steps_cached(next_stone, steps - 1, cache);
+    })
+}
 
-            cache.insert((stone, steps), count);
+pub fn part2(input: &str) -> Result<Num, SolveError> {
+    let stones = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(stones))
+        .ok_or(SolveError::NoAnswer)
+}
 
-            count
-        }
-        _ => unreachable!(),
-    }
+pub fn part2_solve(stones: Vec<Num>) -> Option<Num> {
+    count_stones(&stones, 75)
 }
 
-pub fn part2(input: &str) -> Num {
-    let mut cache: HashMap<(Num, u32), Num> = HashMap::new();
-    input
-        .lines()
-        .next()
-        .unwrap()
-        .split(" ")
-        .map(|n_str| {
-            let n = n_str.parse().unwrap();
+/// Part 1 with the cache of part 2
+pub fn part1_cached(input: &str) -> Result<Num, SolveError> {
+    let stones = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| count_stones(&stones, 25))
+        .ok_or(SolveError::NoAnswer)
+}
 
-            count_steps_cached(n, 75, &mut cache)
-        })
-        .sum()
+/// Number of stones after blinking `blinks` times, `None` when an engraved number overflows
+pub fn count_stones(stones: &[Num], blinks: u32) -> Option<Num> {
+    let mut cache = Memo::new();
+    stones.iter().try_fold(0, |total: Num, &n| {
+        total.checked_add(count_steps_cached(n, blinks, &mut cache)?)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc::indoc! {"
        125 17
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 55312);
+    crate::utils::day_tests! {
+        day: 11,
+        examples: {
+            part1(EXAMPLE) => 55312,
+        },
+        input: {
+            part1 => 204022,
+            part2 => 241651071960597,
+        },
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 204022);
+    fn blinks() {
+        assert_eq!(count_stones(&[125, 17], 6), Some(22));
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 241651071960597);
+    fn overflow() {
+        // 17 and 19 digits fit in a u64 but not once multiplied by 2024
+        for input in ["10000000000000000\n", "1000000000000000000\n"] {
+            assert_eq!(part1(input), Err(SolveError::NoAnswer));
+            assert_eq!(part2(input), Err(SolveError::NoAnswer));
+            assert_eq!(part1_cached(input), Err(SolveError::NoAnswer));
+        }
+        assert_eq!(
+            crate::solve(11, 1, "1000000000000000000\n"),
+            Err(SolveError::NoAnswer)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn cached(stone in 0..1_000_000_u64, steps in 0..20_u32) {
+            let mut cache = Memo::new();
+            prop_assert_eq!(
+                count_steps_cached(stone, steps, &mut cache),
+                count_steps(stone, steps as usize)
+            );
+        }
     }
 }
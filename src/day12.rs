@@ -1,35 +1,70 @@
-use crate::utils::CoordinateAddIter;
+use tracing::info_span;
 
-type Num = u32;
+use crate::utils::{Direction8, DisjointSet, Grid, ParseError};
 
-const GRID_SIZE: usize = 140 + 2;
+type Num = u32;
 
 #[derive(Copy, Clone)]
-struct Plot {
+pub struct Plot {
     plant: u8,
     in_a_region: bool,
 }
 
-pub fn part1(input: &str) -> Num {
+/// Plants are letters, `.` being the border of the grid
+pub fn parse(input: &str) -> Result<Grid<Plot>, ParseError> {
+    let plot = |plant| Plot {
+        plant,
+        in_a_region: false,
+    };
+    let grid = Grid::parse(input, |_, plant| {
+        if !plant.is_ascii_alphabetic() {
+            return Err("a plant letter");
+        }
+        Ok(plot(plant))
+    })?;
+
+    Ok(grid.with_border(plot(b'.')))
+}
+
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let grid = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(grid)))
+}
+
+pub fn part1_solve(grid: Grid<Plot>) -> Num {
+    let mut regions = DisjointSet::from_grid(&grid, |a, b| a.plant == b.plant);
+
+    // Perimeter of each region, at the index of its representative
+    let mut perimeters = vec![0; regions.len()];
+    for (position, plot) in grid.cells().filter(|(_, plot)| plot.plant != b'.') {
+        let region = regions.find(grid.flat_index(position));
+        perimeters[region] += grid
+            .neighbours(position)
+            .filter(|&next| grid[next].plant != plot.plant)
+            .count() as Num;
+    }
+
+    (0..perimeters.len())
+        .filter(|&region| perimeters[region] > 0)
+        .map(|region| regions.size(region) as Num * perimeters[region])
+        .sum()
+}
+
+/// Part 1 labelling the regions with a flood fill from each plot not in a region yet
+pub fn part1_flood_fill(input: &str) -> Result<Num, ParseError> {
+    let mut grid = info_span!("parse").in_scope(|| parse(input))?;
+    let _solve_span = info_span!("solve").entered();
+
     struct Region {
         perimeter: Num,
         area: Num,
     }
 
-    let mut grid = [[Plot {
-        plant: b'.',
-        in_a_region: false,
-    }; GRID_SIZE]; GRID_SIZE];
-
-    input.lines().enumerate().for_each(|(y, l)| {
-        l.bytes().enumerate().for_each(|(x, plant)| {
-            grid[x + 1][y + 1].plant = plant;
-        })
-    });
+    let (height, width) = (grid.height() - 2, grid.width() - 2);
 
     let mut sum = 0;
-    for x in 1..GRID_SIZE {
-        for y in 1..GRID_SIZE {
+    for x in 1..width + 1 {
+        for y in 1..height + 1 {
             let first_plot = grid[y][x];
 
             if first_plot.in_a_region || first_plot.plant == b'.' {
@@ -43,9 +78,9 @@ pub fn part1(input: &str) -> Num {
             };
 
             let mut queue = Vec::new();
-            queue.push((x, y));
+            queue.push((y, x));
 
-            while let Some((x, y)) = queue.pop() {
+            while let Some((y, x)) = queue.pop() {
                 let plot = &mut grid[y][x];
 
                 if plot.plant != first_plot.plant {
@@ -63,17 +98,13 @@ pub fn part1(input: &str) -> Num {
                 plot.in_a_region = true;
                 region.area += 1;
 
-                [(0, 1), (1, 0), (0, -1), (-1, 0)]
-                    .coord_add_iter(x, y)
-                    .for_each(|(dx, dy)| {
-                        queue.push((dx, dy));
-                    });
+                queue.extend(grid.neighbours((y, x)));
             }
 
             sum += region.area * region.perimeter;
         }
     }
-    sum
+    Ok(sum)
 }
 
 fn is_corner(plant: u8, side1: &Plot, side2: &Plot, diagonal: &Plot) -> bool {
@@ -96,27 +127,58 @@ fn is_corner(plant: u8, side1: &Plot, side2: &Plot, diagonal: &Plot) -> bool {
     }
 }
 
-pub fn part2(input: &str) -> Num {
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let grid = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(grid)))
+}
+
+/// Number of corners of the region of the plot at `(y, x)` that are on this plot
+fn corners(grid: &Grid<Plot>, (y, x): (usize, usize)) -> Num {
+    let plant = grid[y][x].plant;
+    [
+        Direction8::NorthWest,
+        Direction8::NorthEast,
+        Direction8::SouthEast,
+        Direction8::SouthWest,
+    ]
+    .into_iter()
+    .filter_map(|diagonal| grid.step((y, x), diagonal))
+    .filter(|&(dy, dx)| is_corner(plant, &grid[dy][x], &grid[y][dx], &grid[dy][dx]))
+    .count() as Num
+}
+
+pub fn part2_solve(grid: Grid<Plot>) -> Num {
+    let mut regions = DisjointSet::from_grid(&grid, |a, b| a.plant == b.plant);
+
+    // A region has as many sides as corners, counted at the index of its representative
+    let mut sides = vec![0; regions.len()];
+    for (position, _) in grid.cells().filter(|(_, plot)| plot.plant != b'.') {
+        let region = regions.find(grid.flat_index(position));
+        sides[region] += corners(&grid, position);
+    }
+
+    (0..sides.len())
+        .filter(|&region| sides[region] > 0)
+        .map(|region| regions.size(region) as Num * sides[region])
+        .sum()
+}
+
+/// Part 2 labelling the regions with a flood fill from each plot not in a region yet
+pub fn part2_flood_fill(input: &str) -> Result<Num, ParseError> {
+    let mut grid = info_span!("parse").in_scope(|| parse(input))?;
+    let _solve_span = info_span!("solve").entered();
+
     #[derive(Debug)]
     struct Region {
         sides: Num,
         area: Num,
     }
 
-    let mut grid = [[Plot {
-        plant: b'.',
-        in_a_region: false,
-    }; GRID_SIZE]; GRID_SIZE];
-
-    input.lines().enumerate().for_each(|(y, l)| {
-        l.bytes().enumerate().for_each(|(x, plant)| {
-            grid[y + 1][x + 1].plant = plant;
-        })
-    });
+    let (height, width) = (grid.height() - 2, grid.width() - 2);
 
     let mut sum = 0;
-    for x in 1..GRID_SIZE {
-        for y in 1..GRID_SIZE {
+    for x in 1..width + 1 {
+        for y in 1..height + 1 {
             let first_plot = grid[y][x];
 
             if first_plot.in_a_region || first_plot.plant == b'.' {
@@ -127,9 +189,9 @@ pub fn part2(input: &str) -> Num {
             let mut region = Region { sides: 0, area: 0 };
 
             let mut queue = Vec::new();
-            queue.push((x, y));
+            queue.push((y, x));
 
-            while let Some((x, y)) = queue.pop() {
+            while let Some((y, x)) = queue.pop() {
                 let plot = &mut grid[y][x];
 
                 if plot.plant != first_plot.plant || plot.in_a_region {
@@ -141,45 +203,16 @@ pub fn part2(input: &str) -> Num {
                 region.area += 1;
                 plot.in_a_region = true;
 
-                // Check for corners
-                [
-                    // Top left
-                    (-1, -1),
-                    // Top right
-                    (-1, 1),
-                    // Bottom right
-                    (1, 1),
-                    // Bottom left
-                    (1, -1),
-                ]
-                .coord_add_iter(x, y)
-                .for_each(|(dx, dy)| {
-                    if is_corner(first_plot.plant, &grid[dy][x], &grid[y][dx], &grid[dy][dx]) {
-                        region.sides += 1;
-                    }
-                });
-
-                [
-                    // Left
-                    (-1, 0),
-                    // Bottom
-                    (0, -1),
-                    // Right
-                    (1, 0),
-                    // Top
-                    (0, 1),
-                ]
-                .coord_add_iter(x, y)
-                .for_each(|(dx, dy)| {
-                    queue.push((dx, dy));
-                });
+                region.sides += corners(&grid, (y, x));
+
+                queue.extend(grid.neighbours((y, x)));
             }
 
             sum += region.area * region.sides;
         }
     }
 
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -223,31 +256,20 @@ ABBAAA
 AAAAAA
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE_1), 140);
-        assert_eq!(part1(EXAMPLE_2), 772);
-        assert_eq!(part1(EXAMPLE_3), 1930);
-
-        assert_eq!(part2(EXAMPLE_1), 80);
-        assert_eq!(part2(EXAMPLE_2), 436);
-        assert_eq!(part2(EXAMPLE_3), 1206);
-        assert_eq!(part2(EXAMPLE_4), 368);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 1464678);
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 877492);
+    crate::utils::day_tests! {
+        day: 12,
+        examples: {
+            part1(EXAMPLE_1) => 140,
+            part1(EXAMPLE_2) => 772,
+            part1(EXAMPLE_3) => 1930,
+            part2(EXAMPLE_1) => 80,
+            part2(EXAMPLE_2) => 436,
+            part2(EXAMPLE_3) => 1206,
+            part2(EXAMPLE_4) => 368,
+        },
+        input: {
+            part1 => 1464678,
+            part2 => 877492,
+        },
     }
 }
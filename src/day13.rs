@@ -1,8 +1,9 @@
-use arrayvec::ArrayVec;
+use tracing::info_span;
 
-type Num = i64;
+use crate::utils::{integer_array, ParseError};
 
-const INPUT_SIZE: usize = 400;
+/// Products of the parsed `i32` values, and part 2 prizes, fit in `i128`
+type Num = i128;
 
 #[derive(Debug)]
 struct Equation {
@@ -11,75 +12,55 @@ struct Equation {
     p: Num,
 }
 
+/// Equations of the claw machine on both axes, `a` and `b` being the button moves
 #[derive(Debug)]
-struct System {
+pub struct System {
     x: Equation,
     y: Equation,
 }
 
-fn parse(input: &str) -> ArrayVec<System, INPUT_SIZE> {
-    input
-        .split("\n\n")
-        .map(|group| {
-            let mut group_lines = group.lines();
-            let regex = regex::Regex::new(r".*X.(?<x>\d+), Y.(?<y>\d+)").unwrap();
-
-            let (a_x, a_y) = group_lines
-                .next()
-                .and_then(|line| regex.captures(line))
-                .map(|caps| {
-                    (
-                        caps.name("x").unwrap().as_str().parse::<Num>().unwrap(),
-                        caps.name("y").unwrap().as_str().parse::<Num>().unwrap(),
-                    )
-                })
-                .unwrap();
-
-            let (b_x, b_y) = group_lines
-                .next()
-                .and_then(|line| regex.captures(line))
-                .map(|caps| {
-                    (
-                        caps.name("x").unwrap().as_str().parse::<Num>().unwrap(),
-                        caps.name("y").unwrap().as_str().parse::<Num>().unwrap(),
-                    )
-                })
-                .unwrap();
-
-            let (p_x, p_y) = group_lines
-                .next()
-                .and_then(|line| regex.captures(line))
-                .map(|caps| {
-                    (
-                        caps.name("x").unwrap().as_str().parse::<Num>().unwrap(),
-                        caps.name("y").unwrap().as_str().parse::<Num>().unwrap(),
-                    )
-                })
-                .unwrap();
-
-            System {
-                x: Equation {
-                    a: a_x,
-                    b: b_x,
-                    p: p_x,
-                },
-                y: Equation {
-                    a: a_y,
-                    b: b_y,
-                    p: p_y,
-                },
-            }
-        })
-        .collect::<ArrayVec<System, INPUT_SIZE>>()
+pub fn parse(input: &str) -> Result<Vec<System>, ParseError> {
+    let mut systems = Vec::new();
+
+    for group in input.trim_end().split("\n\n") {
+        let mut group_lines = group.lines();
+        let mut next_coordinates = |expected: &str| -> Result<(Num, Num), ParseError> {
+            let line = group_lines.next().unwrap_or(&group[group.len()..]);
+            let [x, y] = integer_array::<i32, 2>(input, line, expected)?;
+            Ok((x.into(), y.into()))
+        };
+
+        let (a_x, a_y) = next_coordinates("`Button A: X+_, Y+_`")?;
+        let (b_x, b_y) = next_coordinates("`Button B: X+_, Y+_`")?;
+        let (p_x, p_y) = next_coordinates("`Prize: X=_, Y=_`")?;
+
+        systems.push(System {
+            x: Equation {
+                a: a_x,
+                b: b_x,
+                p: p_x,
+            },
+            y: Equation {
+                a: a_y,
+                b: b_y,
+                p: p_y,
+            },
+        });
+    }
+
+    Ok(systems)
 }
 
-pub fn part1(input: &str) -> Num {
-    let systems = parse(input);
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let systems = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(systems)))
+}
 
+pub fn part1_solve(systems: Vec<System>) -> Num {
     systems
         .into_iter()
         .map(|System { x, y }| {
-            let determinant: i64 = x.a * y.b - x.b * y.a;
+            let determinant = x.a * y.b - x.b * y.a;
 
             if determinant != 0
                 && (y.b * x.p - x.b * y.p) % determinant == 0
@@ -88,7 +69,7 @@ pub fn part1(input: &str) -> Num {
                 let a = (y.b * x.p - x.b * y.p) / determinant;
                 let b = (x.a * y.p - y.a * x.p) / determinant;
 
-                if a <= 100 && b <= 100 {
+                if (0..=100).contains(&a) && (0..=100).contains(&b) {
                     return 3 * a + b;
                 }
             }
@@ -98,15 +79,18 @@ pub fn part1(input: &str) -> Num {
         .sum()
 }
 
-pub fn part2(input: &str) -> Num {
-    let systems = parse(input);
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let systems = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(systems)))
+}
 
+pub fn part2_solve(systems: Vec<System>) -> Num {
     systems
         .into_iter()
         .map(|System { x, y }| {
-            let determinant: i64 = x.a * y.b - x.b * y.a;
+            let determinant = x.a * y.b - x.b * y.a;
 
-            const PART_2_OFFSET: i64 = 10000000000000;
+            const PART_2_OFFSET: Num = 10000000000000;
             let x_p = x.p + PART_2_OFFSET;
             let y_p = y.p + PART_2_OFFSET;
 
@@ -117,7 +101,9 @@ pub fn part2(input: &str) -> Num {
                 let a = (y.b * x_p - x.b * y_p) / determinant;
                 let b = (x.a * y_p - y.a * x_p) / determinant;
 
-                return 3 * a + b;
+                if a >= 0 && b >= 0 {
+                    return 3 * a + b;
+                }
             }
 
             0
@@ -128,6 +114,7 @@ pub fn part2(input: &str) -> Num {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc::indoc! {"
 Button A: X+94, Y+34
@@ -147,25 +134,85 @@ Button B: X+27, Y+71
 Prize: X=18641, Y=10279
 "};
 
+    crate::utils::day_tests! {
+        day: 13,
+        examples: {
+            part1(EXAMPLE) => 480,
+            part2(EXAMPLE) => 875318608908,
+        },
+        input: {
+            part1 => 33209,
+            part2 => 83102355665474,
+        },
+    }
+
     #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 480);
-        assert_eq!(part2(EXAMPLE), 875318608908);
+    fn negative_presses() {
+        let input = "Button A: X+3, Y+1\nButton B: X+1, Y+1\nPrize: X=2, Y=0\n";
+        assert_eq!(part1(input), Ok(0));
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 33209);
+    fn extreme_values() {
+        let input = "Button A: X+2147483647, Y-2147483648\nButton B: X-2147483648, Y+1\nPrize: X=2147483647, Y=-2147483648\n";
+        assert!(part1(input).is_ok());
+        assert!(part2(input).is_ok());
+
+        let input = "Button A: X+2147483648, Y+1\nButton B: X+1, Y+2\nPrize: X=1, Y=1\n";
+        assert!(part1(input).is_err());
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 83102355665474);
+    fn malformed() {
+        let input = "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400\n";
+        assert_eq!(
+            part1(input).unwrap_err().to_string(),
+            "line 3 col 1: expected `Prize: X=_, Y=_`"
+        );
+    }
+
+    /// Prizes reachable with some presses, possibly negative or more than 100, shifted by
+    /// `offset` to make some unreachable
+    fn machine() -> impl Strategy<Value = [Num; 6]> {
+        (
+            prop::array::uniform4(1..100 as Num),
+            -20..=120 as Num,
+            -20..=120 as Num,
+            0..2 as Num,
+        )
+            .prop_map(|([ax, ay, bx, by], a, b, offset)| {
+                [ax, ay, bx, by, a * ax + b * bx + offset, a * ay + b * by]
+            })
+            .prop_filter("prizes are positive", |[.., px, py]| *px >= 0 && *py >= 0)
+    }
+
+    proptest! {
+        #[test]
+        fn brute_force(machines in prop::collection::vec(machine(), 1..4)) {
+            // With collinear buttons, the cheapest presses are not unique
+            prop_assume!(machines.iter().all(|[ax, ay, bx, by, ..]| ax * by != ay * bx));
+
+            let input = machines
+                .iter()
+                .map(|[ax, ay, bx, by, px, py]| {
+                    format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={px}, Y={py}\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let tokens = machines
+                .iter()
+                .map(|[ax, ay, bx, by, px, py]| {
+                    (0..=100)
+                        .flat_map(|a| (0..=100).map(move |b| (a, b)))
+                        .filter(|(a, b)| a * ax + b * bx == *px && a * ay + b * by == *py)
+                        .map(|(a, b)| 3 * a + b)
+                        .min()
+                        .unwrap_or(0)
+                })
+                .sum();
+
+            prop_assert_eq!(part1(&input), Ok(tokens));
+        }
     }
 }
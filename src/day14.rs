@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
-use arrayvec::ArrayVec;
+use tracing::info_span;
 
-type Num = i32;
+use crate::{
+    utils::{integer_array, ParseError},
+    SolveError,
+};
 
-const GUARD_COUNT: usize = 500;
+type Num = u64;
 
 const EXAMPLE_WIDTH: i32 = 11;
 const EXAMPLE_HEIGHT: i32 = 7;
@@ -13,74 +16,82 @@ const INPUT_WIDTH: i32 = 101;
 const INPUT_HEIGHT: i32 = 103;
 
 #[derive(Debug)]
-struct Guard {
-    position: (Num, Num),
+pub struct Guard {
+    position: (i32, i32),
+    /// Speed wrapped into the room, so that moving never overflows
     speed: (i32, i32),
 }
 
-fn parse(input: &str) -> ArrayVec<Guard, GUARD_COUNT> {
-    input
-        .lines()
-        .map(|line| {
-            let (p_str, v_str) = line.split_once(" ").unwrap();
+fn parse_pair(input: &str, s: &str) -> Result<(i32, i32), ParseError> {
+    let [x, y] = integer_array(input, s, "two numbers separated by `,`")?;
 
-            let (p_x, p_y) = p_str[2..]
-                .split_once(",")
-                .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-                .unwrap();
+    Ok((x, y))
+}
 
-            let (v_x, v_y) = v_str[2..]
-                .split_once(",")
-                .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-                .unwrap();
+/// Guards of the input room
+pub fn parse(input: &str) -> Result<Vec<Guard>, ParseError> {
+    parse_inner(input, INPUT_WIDTH, INPUT_HEIGHT)
+}
 
-            Guard {
-                position: (p_x, p_y),
-                speed: (v_x, v_y),
-            }
-        })
-        .collect()
+fn parse_inner(input: &str, width: i32, height: i32) -> Result<Vec<Guard>, ParseError> {
+    let mut guards = Vec::new();
+
+    for line in input.lines() {
+        let p_str = line
+            .strip_prefix("p=")
+            .ok_or_else(|| ParseError::at(input, line, "`p=`"))?;
+        let (p_str, v_str) = p_str
+            .split_once(" ")
+            .unwrap_or((p_str, &p_str[p_str.len()..]));
+        let v_str = v_str
+            .strip_prefix("v=")
+            .ok_or_else(|| ParseError::at(input, v_str, "`v=`"))?;
+
+        let position = parse_pair(input, p_str)?;
+        if !(0..width).contains(&position.0) || !(0..height).contains(&position.1) {
+            let expected = format!("a position inside the {width}x{height} room");
+            return Err(ParseError::at(input, p_str, expected));
+        }
+
+        let (dx, dy) = parse_pair(input, v_str)?;
+        guards.push(Guard {
+            position,
+            speed: (dx.rem_euclid(width), dy.rem_euclid(height)),
+        });
+    }
+
+    Ok(guards)
 }
 
-pub fn part1(input: &str) -> Num {
-    part1_inner(input, INPUT_WIDTH, INPUT_HEIGHT)
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let guards = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(guards)))
 }
 
-pub fn part1_example(input: &str) -> Num {
-    part1_inner(input, EXAMPLE_WIDTH, EXAMPLE_HEIGHT)
+pub fn part1_example(input: &str) -> Result<Num, ParseError> {
+    let guards =
+        info_span!("parse").in_scope(|| parse_inner(input, EXAMPLE_WIDTH, EXAMPLE_HEIGHT))?;
+    Ok(info_span!("solve").in_scope(|| part1_inner(guards, EXAMPLE_WIDTH, EXAMPLE_HEIGHT)))
 }
 
-fn part1_inner(input: &str, width: i32, height: i32) -> Num {
-    let mut guards = parse(input);
+pub fn part1_solve(guards: Vec<Guard>) -> Num {
+    part1_inner(guards, INPUT_WIDTH, INPUT_HEIGHT)
+}
 
+fn part1_inner(mut guards: Vec<Guard>, width: i32, height: i32) -> Num {
     (0..100).for_each(|_| {
         guards.iter_mut().for_each(|guard| {
-            let new_x = guard.position.0 + guard.speed.0;
-            if new_x < 0 {
-                guard.position.0 = new_x + width;
-            } else if new_x >= width {
-                guard.position.0 = new_x - width;
-            } else {
-                guard.position.0 = new_x;
-            }
-
-            let new_y = guard.position.1 + guard.speed.1;
-            if new_y < 0 {
-                guard.position.1 = new_y + height;
-            } else if new_y >= height {
-                guard.position.1 = new_y - height;
-            } else {
-                guard.position.1 = new_y;
-            }
+            guard.position.0 = (guard.position.0 + guard.speed.0) % width;
+            guard.position.1 = (guard.position.1 + guard.speed.1) % height;
         });
     });
 
     #[derive(Default)]
     struct Count {
-        top_left: i32,
-        top_right: i32,
-        bottom_left: i32,
-        bottom_right: i32,
+        top_left: Num,
+        top_right: Num,
+        bottom_left: Num,
+        bottom_right: Num,
     }
     let count = guards
         .into_iter()
@@ -106,17 +117,26 @@ fn part1_inner(input: &str, width: i32, height: i32) -> Num {
     count.top_left * count.bottom_right * count.top_right * count.bottom_left
 }
 
-#[allow(dead_code)]
-fn print_grid(guards: &[Guard], width: i32, height: i32) {
+/// Room with a `#` on the tiles with at least one robot
+#[cfg(feature = "viz")]
+fn render(guards: &[Guard], width: i32, height: i32) -> String {
     let mut grid = vec![vec!['.'; width as usize]; height as usize];
 
     guards.iter().for_each(|guard| {
         grid[guard.position.1 as usize][guard.position.0 as usize] = '#';
     });
 
-    grid.iter().for_each(|row| {
-        println!("{}", row.iter().collect::<String>());
-    });
+    grid.iter()
+        .map(|row| row.iter().chain(['\n'].iter()).collect::<String>())
+        .collect()
+}
+
+/// Room when the robots draw the easter egg of part 2
+#[cfg(feature = "viz")]
+pub fn render_easter_egg(input: &str) -> Result<String, SolveError> {
+    let mut guards = parse(input)?;
+    move_until_easter_egg(&mut guards, INPUT_WIDTH, INPUT_HEIGHT).ok_or(SolveError::NoAnswer)?;
+    Ok(render(&guards, INPUT_WIDTH, INPUT_HEIGHT))
 }
 
 fn check_easter_egg(guards: &[Guard]) -> bool {
@@ -137,51 +157,40 @@ fn check_easter_egg(guards: &[Guard]) -> bool {
     x_count.iter().any(|(_, count)| *count >= 35) && y_count.iter().any(|(_, count)| *count >= 25)
 }
 
-pub fn part2(input: &str) -> Num {
-    part2_inner(input, INPUT_WIDTH, INPUT_HEIGHT)
+pub fn part2(input: &str) -> Result<Num, SolveError> {
+    let guards = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(guards))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part2_example(input: &str) -> Num {
-    part2_inner(input, EXAMPLE_WIDTH, EXAMPLE_HEIGHT)
+pub fn part2_example(input: &str) -> Result<Num, SolveError> {
+    let guards =
+        info_span!("parse").in_scope(|| parse_inner(input, EXAMPLE_WIDTH, EXAMPLE_HEIGHT))?;
+    info_span!("solve")
+        .in_scope(|| part2_inner(guards, EXAMPLE_WIDTH, EXAMPLE_HEIGHT))
+        .ok_or(SolveError::NoAnswer)
 }
 
-fn part2_inner(input: &str, width: i32, height: i32) -> Num {
-    let mut guards = parse(input);
+pub fn part2_solve(guards: Vec<Guard>) -> Option<Num> {
+    part2_inner(guards, INPUT_WIDTH, INPUT_HEIGHT)
+}
 
-    let mut count = 0;
-    loop {
-        count += 1;
+fn part2_inner(mut guards: Vec<Guard>, width: i32, height: i32) -> Option<Num> {
+    move_until_easter_egg(&mut guards, width, height)
+}
 
+/// Move the robots until they draw the easter egg, returning the number of seconds it took.
+/// Positions repeat after `width * height` seconds, `None` when the easter egg is not drawn by then
+fn move_until_easter_egg(guards: &mut [Guard], width: i32, height: i32) -> Option<Num> {
+    (1..=width as Num * height as Num).find(|_| {
         guards.iter_mut().for_each(|guard| {
-            let new_x = guard.position.0 + guard.speed.0;
-            if new_x < 0 {
-                guard.position.0 = new_x + width;
-            } else if new_x >= width {
-                guard.position.0 = new_x - width;
-            } else {
-                guard.position.0 = new_x;
-            }
-
-            let new_y = guard.position.1 + guard.speed.1;
-            if new_y < 0 {
-                guard.position.1 = new_y + height;
-            } else if new_y >= height {
-                guard.position.1 = new_y - height;
-            } else {
-                guard.position.1 = new_y;
-            }
+            guard.position.0 = (guard.position.0 + guard.speed.0) % width;
+            guard.position.1 = (guard.position.1 + guard.speed.1) % height;
         });
 
-        if check_easter_egg(&guards) {
-            return count;
-
-            // print_grid(&guards, width, height);
-            // println!("Count: {count}");
-            // println!("Press Enter to continue...");
-            // let mut buffer = String::new();
-            // std::io::stdin().read_line(&mut buffer).unwrap();
-        }
-    }
+        check_easter_egg(guards)
+    })
 }
 
 #[cfg(test)]
@@ -203,24 +212,52 @@ p=2,4 v=2,-3
 p=9,5 v=-3,-3
 "};
 
+    crate::utils::day_tests! {
+        day: 14,
+        examples: {
+            part1_example(EXAMPLE) => 12,
+        },
+        input: {
+            part1 => 217328832,
+            // part2 => 7412,
+        },
+    }
+
+    #[cfg(all(feature = "viz", feature = "generate"))]
     #[test]
-    fn example() {
-        assert_eq!(part1_example(EXAMPLE), 12);
+    fn render() {
+        // Only the robots of the frame
+        let input = crate::generate::generate(14, 60, 0).unwrap();
+        let room = render_easter_egg(&input).unwrap();
+
+        assert_eq!(room.lines().count(), INPUT_HEIGHT as usize);
+        assert!(room.lines().all(|row| row.len() == INPUT_WIDTH as usize));
+        assert!(room.contains(&"#".repeat(25)));
+        assert_eq!(room.matches('#').count(), 59);
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 217328832);
+    fn no_easter_egg() {
+        // The robots are back where they started before drawing anything
+        assert_eq!(part2_example(EXAMPLE), Err(SolveError::NoAnswer));
+
+        let input = "p=0,0 v=2147483647,-2147483648\np=100,102 v=-2147483648,2147483647\n";
+        assert_eq!(part1(input), Ok(0));
+        assert_eq!(part2(input), Err(SolveError::NoAnswer));
     }
 
-    // #[test]
-    // fn run_part2() {
-    //     let input = crate::utils::get_day_input!();
-    //     let output = part2(&input);
-    //     println!("Part 1: {}", output);
-    //     assert_eq!(output, 7412);
-    // }
+    #[test]
+    fn malformed() {
+        let input = "p=0,4 v=3,-3\np=6,3 w=-1,-3\n";
+        assert_eq!(
+            part1_example(input).unwrap_err().to_string(),
+            "line 2 col 7: expected `v=`"
+        );
+
+        let input = "p=0,4 v=3,-3\np=6,3,1 v=-1,-3\n";
+        assert_eq!(
+            part2_example(input).unwrap_err().to_string(),
+            "line 2 col 3: expected two numbers separated by `,`"
+        );
+    }
 }
@@ -1,115 +1,87 @@
-use arraydeque::ArrayDeque;
-use arrayvec::ArrayVec;
+#[cfg(feature = "viz")]
 use colored::Colorize;
-use std::{
-    fmt,
-    ops::{Index, IndexMut},
-};
+use std::collections::VecDeque;
+use tracing::info_span;
 
-use crate::utils::CoordinateAdd;
+use crate::utils::{grid_dimensions, Direction, Grid, ParseError};
 
 type Num = u32;
 
-const GRID_SIZE: usize = 50;
-
-struct Grid<const W: usize, const H: usize> {
-    inner: ArrayVec<ArrayVec<u8, W>, H>,
-}
-
-impl<const W: usize, const H: usize> FromIterator<ArrayVec<u8, W>> for Grid<W, H> {
-    fn from_iter<T: IntoIterator<Item = ArrayVec<u8, W>>>(iter: T) -> Self {
-        let inner = iter.into_iter().collect();
-        Self { inner }
+/// Split the input into the warehouse and the moves of the robot, checking that the warehouse is
+/// surrounded by walls so that the robot and the boxes can never leave it
+pub fn parse(input: &str) -> Result<(&str, &str), ParseError> {
+    let (grid_str, directions_str) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::end(input, "an empty line between the warehouse and the moves")
+    })?;
+
+    let (height, width) = grid_dimensions(grid_str)?;
+    let mut robot_found = false;
+    for (i, l) in grid_str.lines().enumerate() {
+        for (j, b) in l.bytes().enumerate() {
+            let is_border = i == 0 || i == height - 1 || j == 0 || j == width - 1;
+            match b {
+                b'#' => {}
+                _ if is_border => return Err(ParseError::at(input, &l[j..], "a wall `#`")),
+                b'.' | b'O' => {}
+                b'@' if !robot_found => robot_found = true,
+                _ => {
+                    return Err(ParseError::at(
+                        input,
+                        &l[j..],
+                        "`#`, `.`, `O` or a single `@`",
+                    ))
+                }
+            }
+        }
     }
-}
-
-impl<const W: usize, const H: usize> Index<usize> for Grid<W, H> {
-    type Output = ArrayVec<u8, W>;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.inner[index]
+    if !robot_found {
+        return Err(ParseError::at(
+            input,
+            directions_str,
+            "a robot `@` in the warehouse",
+        ));
     }
-}
 
-impl<const W: usize, const H: usize> IndexMut<usize> for Grid<W, H> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.inner[index]
+    if let Some(i) = directions_str.find(|c| !matches!(c, '^' | 'v' | '<' | '>' | '\n')) {
+        return Err(ParseError::at(
+            input,
+            &directions_str[i..],
+            "a move `^`, `v`, `<` or `>`",
+        ));
     }
-}
-
-impl<const W: usize, const H: usize> IntoIterator for Grid<W, H> {
-    type Item = ArrayVec<u8, W>;
-    type IntoIter = arrayvec::IntoIter<Self::Item, H>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
-    }
+    Ok((grid_str, directions_str))
 }
 
-impl<const W: usize, const H: usize> fmt::Debug for Grid<W, H> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f)?;
-        for (i, l) in self.inner.iter().enumerate() {
-            for (j, b) in l.iter().enumerate() {
-                let c = if (i, j) == (32, 64) {
-                    colored::Color::Yellow
-                } else {
-                    match b {
-                        b'#' => colored::Color::Red,
-                        b'@' => colored::Color::Green,
-                        b'[' | b']' => colored::Color::Blue,
-                        _ => colored::Color::White,
-                    }
-                };
-                write!(f, "{}", (*b as char).to_string().color(c))?;
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
-    }
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
 }
 
-pub fn part1(input: &str) -> Num {
-    let (grid_str, directions_str) = input.split_once("\n\n").unwrap();
-
-    let mut robot = (0, 0);
-    let mut grid: Grid<GRID_SIZE, GRID_SIZE> = grid_str
-        .lines()
-        .enumerate()
-        .map(|(i, l)| {
-            l.bytes()
-                .enumerate()
-                .map(|(j, b)| {
-                    if b == b'@' {
-                        robot = (i, j);
-                    }
+pub fn part1_solve(parsed: (&str, &str)) -> Num {
+    gps_sum(move_boxes(parsed), b'O')
+}
 
-                    b
-                })
-                .collect()
-        })
-        .collect();
+/// Warehouse after all the moves of the robot
+fn move_boxes((grid_str, directions_str): (&str, &str)) -> Grid<u8> {
+    let mut grid = Grid::from_bytes(grid_str).expect("the warehouse is checked when parsing");
+    let mut robot = grid
+        .find(|&b| b == b'@')
+        .expect("the robot is checked when parsing");
 
     directions_str.bytes().for_each(|b| {
         if b == b'\n' {
             return;
         }
 
-        let direction: (i32, i32) = match b {
-            b'^' => (-1, 0),
-            b'v' => (1, 0),
-            b'<' => (0, -1),
-            b'>' => (0, 1),
-            _ => unreachable!(),
-        };
+        let direction = Direction::from_arrow(b).expect("moves are checked when parsing");
 
         let end_of_movement = {
             let mut movement = robot;
             loop {
-                let next = direction.coord_add(movement);
+                let next = step(&grid, movement, direction);
 
-                match grid[next.0][next.1] {
+                match grid[next] {
                     b'#' => break None,
                     b'.' => {
                         break Some(next);
@@ -123,56 +95,43 @@ pub fn part1(input: &str) -> Num {
             }
         };
 
-        if let Some((i, j)) = end_of_movement {
-            let next_robot = direction.coord_add(robot);
-            grid[robot.0][robot.1] = b'.';
-            grid[i][j] = b'O';
-            grid[next_robot.0][next_robot.1] = b'@';
+        if let Some(end) = end_of_movement {
+            let next_robot = step(&grid, robot, direction);
+            grid[robot] = b'.';
+            grid[end] = b'O';
+            grid[next_robot] = b'@';
             robot = next_robot;
         }
     });
 
-    grid.into_iter()
-        .enumerate()
-        .flat_map(|(i, l)| {
-            l.into_iter().enumerate().map(
-                move |(j, b)| {
-                    if b == b'O' {
-                        (i * 100 + j) as Num
-                    } else {
-                        0
-                    }
-                },
-            )
-        })
-        .sum()
+    grid
 }
 
-pub fn part2(input: &str) -> Num {
-    let (grid_str, directions_str) = input.split_once("\n\n").unwrap();
-
-    let mut robot = (0, 0);
-    let mut grid: Grid<{ 2 * GRID_SIZE }, GRID_SIZE> = grid_str
-        .lines()
-        .enumerate()
-        .map(|(i, l)| {
-            l.bytes()
-                .enumerate()
-                .flat_map(|(j, b)| {
-                    use std::iter;
-                    match b {
-                        b'O' => iter::once(b'[').chain(iter::once(b']')),
-                        b'@' => {
-                            robot = (i, 2 * j);
-
-                            iter::once(b'@').chain(iter::once(b'.'))
-                        }
-                        b => iter::once(b).chain(iter::once(b)),
-                    }
-                })
-                .collect()
-        })
-        .collect();
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(parsed)))
+}
+
+pub fn part2_solve(parsed: (&str, &str)) -> Num {
+    gps_sum(move_wide_boxes(parsed), b'[')
+}
+
+/// Warehouse twice as wide after all the moves of the robot
+fn move_wide_boxes((grid_str, directions_str): (&str, &str)) -> Grid<u8> {
+    let narrow = Grid::from_bytes(grid_str).expect("the warehouse is checked when parsing");
+    let mut grid = Grid::new(narrow.height(), 2 * narrow.width(), b'.');
+    for ((i, j), &b) in narrow.cells() {
+        let (left, right) = match b {
+            b'O' => (b'[', b']'),
+            b'@' => (b'@', b'.'),
+            b => (b, b),
+        };
+        grid[i][2 * j] = left;
+        grid[i][2 * j + 1] = right;
+    }
+    let mut robot = grid
+        .find(|&b| b == b'@')
+        .expect("the robot is checked when parsing");
 
     let mut count = 0;
     directions_str.bytes().for_each(|b| {
@@ -181,43 +140,33 @@ pub fn part2(input: &str) -> Num {
             return;
         }
 
-        let (direction, horizontal): ((i32, i32), bool) = match b {
-            b'^' => ((-1, 0), false),
-            b'v' => ((1, 0), false),
-            b'<' => ((0, -1), true),
-            b'>' => ((0, 1), true),
-            _ => unreachable!(),
-        };
+        let direction = Direction::from_arrow(b).expect("moves are checked when parsing");
+        let horizontal = matches!(direction, Direction::East | Direction::West);
 
-        const CAP: usize = GRID_SIZE * 3;
-        let mut boxes_to_move = ArrayVec::<(usize, usize), CAP>::new();
+        let mut boxes_to_move = Vec::new();
         let mut can_move = true;
-        let mut queue = ArrayDeque::<_, CAP>::new();
-        queue.push_back(direction.coord_add(robot)).unwrap();
+        let mut queue = VecDeque::new();
+        queue.push_back(step(&grid, robot, direction));
 
         while let Some((i, j)) = queue.pop_front() {
             match grid[i][j] {
                 b'[' => {
                     boxes_to_move.push((i, j));
                     if horizontal {
-                        queue
-                            .push_back(direction.coord_add(direction.coord_add((i, j))))
-                            .unwrap();
+                        queue.push_back(step(&grid, step(&grid, (i, j), direction), direction));
                     } else {
-                        queue.push_back(direction.coord_add((i, j))).unwrap();
-                        queue.push_back(direction.coord_add((i, j + 1))).unwrap();
+                        queue.push_back(step(&grid, (i, j), direction));
+                        queue.push_back(step(&grid, (i, j + 1), direction));
                     }
                 }
                 b']' => {
                     // Left box
                     boxes_to_move.push((i, j - 1));
                     if horizontal {
-                        queue
-                            .push_back(direction.coord_add(direction.coord_add((i, j))))
-                            .unwrap();
+                        queue.push_back(step(&grid, step(&grid, (i, j), direction), direction));
                     } else {
-                        queue.push_back(direction.coord_add((i, j - 1))).unwrap();
-                        queue.push_back(direction.coord_add((i, j))).unwrap();
+                        queue.push_back(step(&grid, (i, j - 1), direction));
+                        queue.push_back(step(&grid, (i, j), direction));
                     }
                 }
                 b'#' => {
@@ -233,7 +182,7 @@ pub fn part2(input: &str) -> Num {
         if can_move {
             // Move all the boxes starting from the last one to avoid overwriting changes
             boxes_to_move.into_iter().rev().for_each(|box_left| {
-                let new_box_left = direction.coord_add(box_left);
+                let new_box_left = step(&grid, box_left, direction);
                 // Remove old box
                 grid[box_left.0][box_left.1] = b'.';
                 grid[box_left.0][box_left.1 + 1] = b'.';
@@ -243,26 +192,59 @@ pub fn part2(input: &str) -> Num {
             });
 
             // Move robot
-            let next_robot = direction.coord_add(robot);
-            grid[robot.0][robot.1] = b'.';
-            grid[next_robot.0][next_robot.1] = b'@';
+            let next_robot = step(&grid, robot, direction);
+            grid[robot] = b'.';
+            grid[next_robot] = b'@';
             robot = next_robot;
         }
     });
 
-    grid.into_iter()
-        .enumerate()
-        .flat_map(|(i, l)| {
-            l.into_iter().enumerate().map(
-                move |(j, b)| {
-                    if b == b'[' {
-                        (i * 100 + j) as Num
-                    } else {
-                        0
-                    }
-                },
-            )
-        })
+    grid
+}
+
+/// `position` moved once towards `direction`, the walls around the warehouse keep it inside
+fn step(grid: &Grid<u8>, position: (usize, usize), direction: Direction) -> (usize, usize) {
+    grid.step(position, direction)
+        .expect("walls are checked when parsing")
+}
+
+/// Walls in red, the robot in green and boxes in blue
+#[cfg(feature = "viz")]
+fn render(grid: &Grid<u8>) -> String {
+    let mut rendered = String::with_capacity(grid.height() * (grid.width() + 1));
+    for row in grid.rows() {
+        for &b in row {
+            let color = match b {
+                b'#' => colored::Color::Red,
+                b'@' => colored::Color::Green,
+                b'O' | b'[' | b']' => colored::Color::Blue,
+                _ => colored::Color::White,
+            };
+            rendered += &(b as char).to_string().color(color).to_string();
+        }
+        rendered.push('\n');
+    }
+
+    rendered
+}
+
+/// Warehouse of part 1 after all the moves of the robot
+#[cfg(feature = "viz")]
+pub fn render_part1(input: &str) -> Result<String, ParseError> {
+    Ok(render(&move_boxes(parse(input)?)))
+}
+
+/// Warehouse of part 2 after all the moves of the robot
+#[cfg(feature = "viz")]
+pub fn render_part2(input: &str) -> Result<String, ParseError> {
+    Ok(render(&move_wide_boxes(parse(input)?)))
+}
+
+/// Sum of the GPS coordinates of the boxes, `box_edge` being their left edge
+fn gps_sum(grid: Grid<u8>, box_edge: u8) -> Num {
+    grid.cells()
+        .filter(|&(_, &b)| b == box_edge)
+        .map(|((i, j), _)| (i * 100 + j) as Num)
         .sum()
 }
 
@@ -319,28 +301,37 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
 <vv<<^^<<^^
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(SMALL_EXAMPLE), 2028);
-        assert_eq!(part1(EXAMPLE), 10092);
-        assert_eq!(part2(SMALL_EXAMPLE_PART2), 618);
-        assert_eq!(part2(EXAMPLE), 9021);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 1479679);
+    crate::utils::day_tests! {
+        day: 15,
+        examples: {
+            part1(SMALL_EXAMPLE) => 2028,
+            part1(EXAMPLE) => 10092,
+            part2(SMALL_EXAMPLE_PART2) => 618,
+            part2(EXAMPLE) => 9021,
+        },
+        input: {
+            part1 => 1479679,
+            part2 => 1509780,
+        },
     }
 
+    #[cfg(feature = "viz")]
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 1509780);
+    fn render() {
+        colored::control::set_override(false);
+        assert_eq!(
+            render_part1(SMALL_EXAMPLE).unwrap(),
+            indoc::indoc! {"
+                ########
+                #....OO#
+                ##.....#
+                #.....O#
+                #.#O@..#
+                #...O..#
+                #...O..#
+                ########
+            "}
+        );
     }
 
     #[test]
@@ -359,6 +350,6 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
 <^<^<v
 "};
 
-        assert_eq!(part2(debug_input), 3328);
+        assert_eq!(part2(debug_input), Ok(3328));
     }
 }
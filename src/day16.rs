@@ -1,207 +1,107 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
-};
+use std::collections::HashSet;
+use tracing::info_span;
 
-use crate::utils::CoordinateAdd;
+use crate::{
+    utils::{dijkstra, grid_dimensions, Direction, Grid, ParseError},
+    SolveError,
+};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+/// Position of the reindeer and the direction it faces
+type Node = ((usize, usize), Direction);
+
+/// Moving forward, or turning then moving, with the cost of the move
+fn next_moves(
+    grid: &Grid<u8>,
+    (position, facing): Node,
+) -> impl Iterator<Item = (Node, usize)> + '_ {
+    [facing, facing.turn_left(), facing.turn_right()]
+        .into_iter()
+        .filter_map(move |direction| {
+            let next = grid.step(position, direction)?;
+            let cost = if direction == facing { 1 } else { 1001 };
+            (grid[next] != b'#').then_some(((next, direction), cost))
+        })
 }
 
-impl Direction {
-    fn next(&self) -> [((i32, i32), Direction); 3] {
-        match self {
-            Direction::Up => [
-                ((-1, 0), Direction::Up),
-                ((0, -1), Direction::Left),
-                ((0, 1), Direction::Right),
-            ],
-            Direction::Down => [
-                ((1, 0), Direction::Down),
-                ((0, 1), Direction::Right),
-                ((0, -1), Direction::Left),
-            ],
-            Direction::Left => [
-                ((0, -1), Direction::Left),
-                ((-1, 0), Direction::Up),
-                ((1, 0), Direction::Down),
-            ],
-            Direction::Right => [
-                ((0, 1), Direction::Right),
-                ((1, 0), Direction::Down),
-                ((-1, 0), Direction::Up),
-            ],
+/// The maze must be surrounded by walls, so that the reindeer never leaves it
+pub fn parse(input: &str) -> Result<(Grid<u8>, (usize, usize)), ParseError> {
+    let (height, width) = grid_dimensions(input)?;
+    let mut start = None;
+    let mut end = None;
+
+    let grid = Grid::parse(input, |(i, j), c| {
+        let is_border = i == 0 || i == height - 1 || j == 0 || j == width - 1;
+        match c {
+            b'#' => {}
+            _ if is_border => return Err("a wall `#`"),
+            b'.' => {}
+            b'S' if start.is_none() => start = Some((i, j)),
+            b'E' if end.is_none() => end = Some((i, j)),
+            _ => return Err("`#`, `.`, a single `S` or a single `E`"),
         }
-    }
-}
-
-#[derive(Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    x: usize,
-    y: usize,
-    direction: Direction,
-}
+        Ok(c)
+    })?;
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Comparison is flipped to make the heap pop the smallest cost first
-        other.cost.cmp(&self.cost)
-    }
-}
+    let start = start.ok_or_else(|| ParseError::end(input, "a start `S`"))?;
+    end.ok_or_else(|| ParseError::end(input, "an end `E`"))?;
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+    Ok((grid, start))
 }
 
-const SIZE: usize = 145;
-
-fn parse(input: &str) -> ([[u8; SIZE]; SIZE], (usize, usize)) {
-    let mut grid = [[b' '; SIZE]; SIZE];
-    let mut start = (0, 0);
-
-    input.lines().enumerate().for_each(|(i, l)| {
-        l.bytes().enumerate().for_each(|(j, c)| {
-            grid[i][j] = c;
-            if c == b'S' {
-                start = (i, j);
-            }
-        });
-    });
-
-    (grid, start)
-}
-
-pub fn part1(input: &str) -> usize {
-    let (grid, start) = parse(input);
-    let mut visited = [[false; SIZE]; SIZE];
-    let mut p_queue = BinaryHeap::with_capacity(SIZE * SIZE);
-
-    p_queue.push(State {
-        cost: 0,
-        x: start.0,
-        y: start.1,
-        direction: Direction::Right,
-    });
-
-    while let Some(cur) = p_queue.pop() {
-        if grid[cur.x][cur.y] == b'E' {
-            return cur.cost;
-        }
-        if visited[cur.x][cur.y] {
-            continue;
-        }
-        visited[cur.x][cur.y] = true;
-
-        for (next, direction) in cur.direction.next() {
-            let next = next.coord_add((cur.x, cur.y));
-            if grid[next.0][next.1] == b'#' {
-                continue;
-            }
-            p_queue.push(State {
-                cost: cur.cost + if direction == cur.direction { 1 } else { 1001 },
-                x: next.0,
-                y: next.1,
-                direction,
-            });
-        }
-    }
-
-    unreachable!()
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-#[derive(Clone, Eq, PartialEq)]
-struct StateWithPath {
-    cost: usize,
-    coords: (usize, usize),
-    direction: Direction,
-    path: Vec<(usize, usize)>,
-}
+/// Lowest score to reach the end, `None` when walls block the way
+pub fn part1_solve((grid, start): (Grid<u8>, (usize, usize))) -> Option<usize> {
+    let _dijkstra_span = info_span!("dijkstra").entered();
+    let search = dijkstra(
+        [(start, Direction::East)],
+        |node| next_moves(&grid, node),
+        |(position, _)| grid[position] == b'E',
+    );
 
-impl Ord for StateWithPath {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Comparison is flipped to make the heap pop the smallest cost first
-        other.cost.cmp(&self.cost)
-    }
+    search.goal().map(|(_, cost)| cost)
 }
 
-impl PartialOrd for StateWithPath {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part2(input: &str) -> usize {
-    let (grid, start) = parse(input);
-    let mut p_queue = BinaryHeap::with_capacity(SIZE * SIZE);
-
-    p_queue.push(StateWithPath {
-        cost: 0,
-        coords: start,
-        direction: Direction::Right,
-        path: vec![start],
-    });
-
-    let mut best_path_grid = grid;
-    let mut min_cost = usize::MAX;
-    let mut visited: HashMap<((usize, usize), Direction), usize> = HashMap::new();
-
-    while let Some(u) = p_queue.pop() {
-        if grid[u.coords.0][u.coords.1] == b'E' {
-            min_cost = min_cost.min(u.cost);
-            if u.cost > min_cost {
-                // We have found all the paths with the minimum cost, stop here
-                break;
-            }
-            u.path.iter().for_each(|&(x, y)| {
-                best_path_grid[x][y] = b'O';
-            });
-        }
-        if let Some(&cost) = visited.get(&(u.coords, u.direction)) {
-            if u.cost > cost {
-                continue;
-            }
-        } else {
-            visited.insert((u.coords, u.direction), u.cost);
-        }
-
-        for (v, direction) in u.direction.next() {
-            let next = v.coord_add(u.coords);
-            if grid[next.0][next.1] == b'#' {
-                continue;
-            }
-            let n_cost = u.cost + if direction == u.direction { 1 } else { 1001 };
-            if let Some(&cost) = visited.get(&(next, direction)) {
-                if n_cost >= cost {
-                    continue;
-                }
-            }
-            p_queue.push(StateWithPath {
-                cost: n_cost,
-                coords: next,
-                direction,
-                path: u
-                    .path
-                    .clone()
-                    .into_iter()
-                    .chain(std::iter::once(next))
-                    .collect(),
-            });
-        }
-    }
-
-    best_path_grid
-        .iter()
-        .flatten()
-        .filter(|&&b| b == b'O')
-        .count()
+/// Number of tiles on the best paths, `None` when walls block the way
+pub fn part2_solve((grid, start): (Grid<u8>, (usize, usize))) -> Option<usize> {
+    let dijkstra_span = info_span!("dijkstra").entered();
+    // The end can be reached facing several directions, explore everything
+    let search = dijkstra(
+        [(start, Direction::East)],
+        |node| next_moves(&grid, node),
+        |_| false,
+    );
+    dijkstra_span.exit();
+
+    let ends = search
+        .costs()
+        .filter(|&((position, _), _)| grid[position] == b'E')
+        .collect::<Vec<_>>();
+    let min_cost = ends.iter().map(|&(_, cost)| cost).min()?;
+
+    let tiles = search
+        .shortest_paths_dag(
+            ends.into_iter()
+                .filter(|&(_, cost)| cost == min_cost)
+                .map(|(end, _)| end),
+        )
+        .into_keys()
+        .map(|(position, _)| position)
+        .collect::<HashSet<_>>();
+
+    Some(tiles.len())
 }
 
 #[cfg(test)]
@@ -276,28 +176,25 @@ mod tests {
 ###########################
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 7036);
-        assert_eq!(part1(EXAMPLE2), 11048);
-        assert_eq!(part1(EXAMPLE3), 21148);
-        assert_eq!(part2(EXAMPLE), 45);
-        assert_eq!(part2(EXAMPLE2), 64);
-    }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 160624);
+    crate::utils::day_tests! {
+        day: 16,
+        examples: {
+            part1(EXAMPLE) => 7036,
+            part1(EXAMPLE2) => 11048,
+            part1(EXAMPLE3) => 21148,
+            part2(EXAMPLE) => 45,
+            part2(EXAMPLE2) => 64,
+        },
+        input: {
+            part1 => 160624,
+            part2 => 692,
+        },
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 692);
+    fn walled_end() {
+        let input = "#####\n#S#E#\n#####\n";
+        assert_eq!(part1(input), Err(SolveError::NoAnswer));
+        assert_eq!(part2(input), Err(SolveError::NoAnswer));
     }
 }
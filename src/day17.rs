@@ -1,12 +1,18 @@
-use arrayvec::ArrayVec;
 use itertools::Itertools;
+use tracing::info_span;
+
+use crate::{
+    utils::{integers, parse_number, ParseError},
+    SolveError,
+};
 
 type Num = usize;
 
-const INSTRUCTIONS_SIZE: usize = 20;
+/// Instructions executed before a program is considered to never halt
+const MAX_STEPS: usize = 1 << 16;
 
 #[derive(Debug, Default, Clone)]
-struct Registers {
+pub struct Registers {
     a: Num,
     b: Num,
     c: Num,
@@ -42,8 +48,17 @@ impl From<u8> for OpCode {
     }
 }
 
+/// `value` divided by `2^shift`, without overflowing on large shifts
+fn shift_right(value: Num, shift: Num) -> Num {
+    u32::try_from(shift)
+        .ok()
+        .and_then(|shift| value.checked_shr(shift))
+        .unwrap_or(0)
+}
+
 impl Registers {
-    fn execute(&mut self, program: &[u8]) {
+    /// Execute the instruction at `pc`, `None` on the reserved combo operand 7
+    fn execute(&mut self, program: &[u8]) -> Option<()> {
         let op = OpCode::from(program[self.pc]);
         let arg = match op {
             OpCode::Bxl | OpCode::Jnz => program[self.pc + 1] as usize,
@@ -51,13 +66,14 @@ impl Registers {
                 4 => self.a,
                 5 => self.b,
                 6 => self.c,
+                7 => return None,
                 n => n as usize,
             },
         };
 
         match op {
             OpCode::Adv => {
-                self.a >>= arg;
+                self.a = shift_right(self.a, arg);
             }
             OpCode::Bxl => {
                 self.b ^= arg;
@@ -68,7 +84,7 @@ impl Registers {
             OpCode::Jnz => {
                 if self.a != 0 {
                     self.pc = arg;
-                    return; // Don't increment pc by 2 like the other instructions
+                    return Some(()); // Don't increment pc by 2 like the other instructions
                 }
             }
             OpCode::Bxc => {
@@ -78,114 +94,140 @@ impl Registers {
                 self.out.push(arg as u8 & 0b111);
             }
             OpCode::Bdv => {
-                self.b = self.a >> arg;
+                self.b = shift_right(self.a, arg);
             }
             OpCode::Cdv => {
-                self.c = self.a >> arg;
+                self.c = shift_right(self.a, arg);
             }
         }
 
         self.pc += 2;
+        Some(())
     }
 
-    fn execute_all(&mut self, program: &[u8]) {
-        while self.pc < program.len() {
-            self.execute(program);
+    /// Run the program until it halts, `None` when it does not halt within `MAX_STEPS`
+    /// instructions or uses the reserved combo operand
+    fn execute_all(&mut self, program: &[u8]) -> Option<()> {
+        for _ in 0..MAX_STEPS {
+            // Jumps to an odd address can leave the opcode without its operand
+            if self.pc + 1 >= program.len() {
+                return Some(());
+            }
+            self.execute(program)?;
         }
+
+        None
     }
 }
 
-pub fn part1(input: &str) -> String {
-    let mut reg = Registers::default();
-
-    let program: ArrayVec<u8, INSTRUCTIONS_SIZE> = {
-        let mut input = input.lines();
-        reg.a = input
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
-            .1
-            .parse()
-            .unwrap();
-
-        // B & C are always 0, skip them and the empty line after them
-        for _ in 0..3 {
-            input.next();
-        }
+/// Initial registers and the program, as 3-bit numbers
+pub fn parse(input: &str) -> Result<(Registers, Vec<u8>), ParseError> {
+    let mut lines = input.lines();
+    let mut register = |name: &str| -> Result<Num, ParseError> {
+        let prefix = format!("Register {name}: ");
+        let line = lines.next().unwrap_or(&input[input.len()..]);
+        let value = line
+            .strip_prefix(&prefix)
+            .ok_or_else(|| ParseError::at(input, line, format!("`{prefix}`")))?;
+
+        parse_number(input, value)
+    };
 
-        input
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
-            .1
-            .split(',')
-            .flat_map(|p| p.parse())
-            .collect()
+    let reg = Registers {
+        a: register("A")?,
+        b: register("B")?,
+        c: register("C")?,
+        ..Default::default()
     };
 
-    reg.execute_all(&program);
+    let line = lines
+        .find(|line| !line.is_empty())
+        .unwrap_or(&input[input.len()..]);
+    let values = line
+        .strip_prefix("Program: ")
+        .ok_or_else(|| ParseError::at(input, line, "`Program: `"))?;
+
+    let program = integers::<u8>(input, values).collect::<Result<Vec<_>, _>>()?;
+    if program.is_empty() || program.iter().any(|&n| n >= 8) {
+        return Err(ParseError::at(
+            input,
+            values,
+            "3-bit numbers separated by `,`",
+        ));
+    }
+    if program.len() % 2 != 0 {
+        let end = &line[line.len()..];
+        return Err(ParseError::at(input, end, "an operand after each opcode"));
+    }
 
-    reg.out.iter().join(",")
+    Ok((reg, program))
 }
 
-pub fn part2(input: &str) -> Num {
-    let (reg, program): (Registers, ArrayVec<u8, INSTRUCTIONS_SIZE>) = {
-        let mut reg = Registers::default();
-        let mut input = input.lines();
-        reg.a = input
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
-            .1
-            .parse()
-            .unwrap();
-
-        // B & C are always 0, skip them and the empty line after them
-        for _ in 0..3 {
-            input.next();
-        }
+pub fn part1(input: &str) -> Result<String, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-        (
-            reg,
-            input
-                .next()
-                .unwrap()
-                .split_once(": ")
-                .unwrap()
-                .1
-                .split(',')
-                .flat_map(|p| p.parse())
-                .collect(),
-        )
-    };
+/// Output of the program, `None` when it never halts
+pub fn part1_solve((mut reg, program): (Registers, Vec<u8>)) -> Option<String> {
+    reg.execute_all(&program)?;
 
-    // Build the number from right to left
-    let mut queue = vec![(0, program.len() - 1)];
-    let mut values = Vec::new();
+    Some(reg.out.iter().join(","))
+}
+
+/// Lowest value of register A for which the program outputs itself, if any
+fn lowest_quine(reg: &Registers, program: &[u8]) -> Option<Num> {
+    // Build the number from right to left, all candidates have as many 3-bit digits so the
+    // first one found, exploring the lowest digits first, is the lowest
+    let mut queue: Vec<(Num, usize)> = vec![(0, program.len() - 1)];
     while let Some((partial_input, value_to_print_index)) = queue.pop() {
-        for i in 0..8 {
-            let candidate = (partial_input << 3) + i;
+        let Some(shifted) = partial_input.checked_mul(8) else {
+            continue;
+        };
+
+        let mut candidates = (0..8).map(|i| shifted + i).filter(|&candidate| {
             let mut candidate_reg = Registers {
                 a: candidate,
                 ..reg.clone()
             };
 
-            candidate_reg.execute_all(&program);
+            candidate_reg.execute_all(program).is_some()
+                && candidate_reg.out == program[value_to_print_index..]
+        });
 
-            if candidate_reg.out == program[value_to_print_index..] {
-                if value_to_print_index == 0 {
-                    values.push(candidate);
-                } else {
-                    queue.push((candidate, value_to_print_index - 1));
-                }
+        if value_to_print_index == 0 {
+            if let Some(candidate) = candidates.next() {
+                return Some(candidate);
             }
+        } else {
+            queue.extend(
+                candidates
+                    .rev()
+                    .map(|candidate| (candidate, value_to_print_index - 1)),
+            );
         }
     }
 
-    values.into_iter().min().unwrap()
+    None
+}
+
+pub fn part2(input: &str) -> Result<Num, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
+
+pub fn part2_solve((reg, program): (Registers, Vec<u8>)) -> Option<Num> {
+    lowest_quine(&reg, &program)
+}
+
+/// Whether part 2 has an answer, for generated programs
+#[cfg(feature = "generate")]
+pub(crate) fn has_quine(input: &str) -> bool {
+    parse(input).is_ok_and(|(reg, program)| lowest_quine(&reg, &program).is_some())
 }
 
 #[cfg(test)]
@@ -208,10 +250,63 @@ Register C: 0
 Program: 0,3,5,4,3,0
 "};
 
+    crate::utils::day_tests! {
+        day: 17,
+        examples: {
+            part1(EXAMPLE) => "4,6,3,5,6,3,5,2,1,0",
+            part2(EXAMPLE2) => 117440,
+        },
+        input: {
+            part1 => "4,1,5,3,1,5,3,5,7",
+            part2 => 164542125272765,
+        },
+    }
+
+    #[test]
+    fn no_quine() {
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 1,1\n";
+        assert_eq!(part2(input), Err(SolveError::NoAnswer));
+    }
+
     #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), "4,6,3,5,6,3,5,2,1,0");
-        assert_eq!(part2(EXAMPLE2), 117440);
+    fn malformed() {
+        let registers = "Register A: 0\nRegister B: 0\nRegister C: 0\n\n";
+        assert_eq!(
+            part1(&format!("{registers}Program: 0,8\n"))
+                .unwrap_err()
+                .to_string(),
+            "line 5 col 10: expected 3-bit numbers separated by `,`"
+        );
+        assert_eq!(
+            part2(&format!("{registers}Program: \n"))
+                .unwrap_err()
+                .to_string(),
+            "line 5 col 10: expected 3-bit numbers separated by `,`"
+        );
+        assert_eq!(
+            part2(&format!("{registers}Program: 0,3,5\n"))
+                .unwrap_err()
+                .to_string(),
+            "line 5 col 15: expected an operand after each opcode"
+        );
+    }
+
+    #[test]
+    fn never_halts() {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 5,1,5,5,3,0\n";
+        assert_eq!(part1(input), Err(SolveError::NoAnswer));
+        assert_eq!(part2(input), Err(SolveError::NoAnswer));
+
+        // Reserved combo operand
+        let input = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 5,7\n";
+        assert_eq!(part1(input), Err(SolveError::NoAnswer));
+    }
+
+    #[test]
+    fn odd_jump() {
+        // Shifts past the register size, then a jump to the last operand halts
+        let input = "Register A: 100\nRegister B: 0\nRegister C: 0\n\nProgram: 6,4,5,5,3,3\n";
+        assert_eq!(part1(input), Ok("0,3".to_string()));
     }
 
     #[test]
@@ -288,20 +383,4 @@ Program: 0,3,5,4,3,0
             vec![2, 4, 1, 1, 7, 5, 1, 5, 0, 3, 4, 3, 5, 5, 3, 0]
         );
     }
-
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, "4,1,5,3,1,5,3,5,7");
-    }
-
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 164542125272765);
-    }
 }
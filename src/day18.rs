@@ -1,109 +1,100 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
+use tracing::{debug_span, info_span};
 
-use crate::utils::CoordinateAdd;
+use crate::{
+    utils::{bfs, integer_array, DisjointSet, Grid, ParseError},
+    SolveError,
+};
 
 type Num = u32;
 
-const INPUT_SIZE: usize = 71 + 2;
+const EXAMPLE_SIDE: usize = 7;
+const EXAMPLE_LIMIT: usize = 12;
 
-fn parse_input<const SIZE: usize>(input: &str, limit: usize) -> [[u8; SIZE]; SIZE] {
-    let mut grid = [[b'.'; SIZE]; SIZE];
+const INPUT_SIDE: usize = 71;
+const INPUT_LIMIT: usize = 1024;
 
-    for i in 0..SIZE {
-        grid[i][0] = b'#';
-        grid[i][SIZE - 1] = b'#';
-        grid[0][i] = b'#';
-        grid[SIZE - 1][i] = b'#';
-    }
+/// Coordinates of a falling byte, along with the line they come from
+type Byte<'a> = (&'a str, (usize, usize));
 
-    input.lines().take(limit).for_each(|l| {
-        let (i, j) = l
-            .split_once(",")
-            .map(|(a, b)| (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap()))
-            .unwrap();
-        grid[i + 1][j + 1] = b'#';
-    });
-
-    grid
+/// Falling bytes in the memory space of the input, and its side once surrounded by a border
+pub fn parse(input: &str) -> Result<(Vec<Byte<'_>>, usize), ParseError> {
+    parse_inner(input, INPUT_SIDE)
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct State {
-    i: usize,
-    j: usize,
-    cost: Num,
+/// Falling bytes in a memory space of `side` cells, for the example and generated inputs
+pub fn parse_inner(input: &str, side: usize) -> Result<(Vec<Byte<'_>>, usize), ParseError> {
+    let bytes = input
+        .lines()
+        .map(|l| {
+            let [a, b] = integer_array(input, l, "two numbers separated by `,`")?;
+            if a >= side || b >= side {
+                let expected = format!("coordinates inside the {side}x{side} memory space");
+                return Err(ParseError::at(input, l, expected));
+            }
+
+            Ok((l, (a, b)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((bytes, side + 2))
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+fn fill_grid(bytes: &[Byte], size: usize) -> Grid<u8> {
+    let mut grid = Grid::new(size - 2, size - 2, b'.').with_border(b'#');
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Comparison is flipped to make the heap pop the smallest cost first
-        other.cost.cmp(&self.cost)
-    }
-}
+    bytes.iter().for_each(|(_, (i, j))| {
+        grid[i + 1][j + 1] = b'#';
+    });
 
-fn part1_inner<const SIZE: usize>(input: &str, limit: usize) -> Num {
-    let grid = parse_input::<SIZE>(input, limit);
+    grid
+}
 
-    // Let's dijkstra 😎
-    let mut queue = BinaryHeap::with_capacity(SIZE * SIZE);
-    let mut visited = [[false; SIZE]; SIZE];
+/// Steps to the end once `limit` bytes have fallen, `None` when they block the way
+fn part1_inner((bytes, size): (Vec<Byte>, usize), limit: usize) -> Option<Num> {
+    let grid = fill_grid(&bytes[..limit.min(bytes.len())], size);
+    let end = (size - 2, size - 2);
+    if grid[(1, 1)] == b'#' || grid[end] == b'#' {
+        return None;
+    }
 
-    queue.push(State {
-        i: 1,
-        j: 1,
-        cost: 0,
-    });
+    let search = bfs(
+        [(1, 1)],
+        |position| grid.neighbours(position).filter(|&next| grid[next] != b'#'),
+        |position| position == end,
+    );
 
-    while let Some(u) = queue.pop() {
-        if (u.i, u.j) == (SIZE - 2, SIZE - 2) {
-            return u.cost;
-        }
+    search.goal().map(|(_, steps)| steps as Num)
+}
 
-        if visited[u.i][u.j] {
-            continue;
-        }
-        visited[u.i][u.j] = true;
-
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((u.i, u.j));
-
-                if grid[i][j] == b'#' {
-                    return;
-                }
-
-                queue.push(State {
-                    i,
-                    j,
-                    cost: u.cost + 1,
-                });
-            });
-    }
+pub fn part1(input: &str) -> Result<Num, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-    unreachable!()
+pub fn part1_example(input: &str) -> Result<Num, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse_inner(input, EXAMPLE_SIDE))?;
+    info_span!("solve")
+        .in_scope(|| part1_inner(parsed, EXAMPLE_LIMIT))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part1(input: &str) -> Num {
-    part1_inner::<INPUT_SIZE>(input, 1024)
+pub fn part1_solve(parsed: (Vec<Byte>, usize)) -> Option<Num> {
+    part1_inner(parsed, INPUT_LIMIT)
 }
 
-fn is_end_reachable<const SIZE: usize>(input: &str, limit: usize) -> bool {
-    let grid = parse_input::<SIZE>(input, limit);
+fn is_end_reachable(bytes: &[Byte], size: usize) -> bool {
+    let _span = debug_span!("is_end_reachable", limit = bytes.len()).entered();
+    let grid = fill_grid(bytes, size);
     let mut queue = Vec::new();
-    let mut visited = [[false; SIZE]; SIZE];
+    let mut visited = Grid::new(size, size, false);
 
     queue.push((1, 1));
 
     // DFS to find the end
     while let Some((i, j)) = queue.pop() {
-        if (i, j) == (SIZE - 2, SIZE - 2) {
+        if (i, j) == (size - 2, size - 2) {
             return true;
         }
 
@@ -116,30 +107,74 @@ fn is_end_reachable<const SIZE: usize>(input: &str, limit: usize) -> bool {
         }
         visited[i][j] = true;
 
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((i, j));
-                queue.push((i, j));
-            });
+        queue.extend(grid.neighbours((i, j)));
     }
 
     false
 }
 
-fn part2_inner<const SIZE: usize>(input: &str) -> &str {
-    let lines = input.lines().collect::<Vec<_>>();
+pub fn part2(input: &str) -> Result<&str, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-    lines
-        .iter()
-        .enumerate()
-        .find(|(i, _l)| !is_end_reachable::<SIZE>(input, *i + 1))
-        .unwrap()
-        .1
+pub fn part2_example(input: &str) -> Result<&str, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse_inner(input, EXAMPLE_SIDE))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part2(input: &str) -> &str {
-    part2_inner::<INPUT_SIZE>(input)
+/// First byte cutting the end off the start, `None` when the way stays open
+pub fn part2_solve<'a>((bytes, size): (Vec<Byte<'a>>, usize)) -> Option<&'a str> {
+    // Index of the first byte falling on each cell, the border being a wall that is never free
+    let mut fallen = Grid::new(size - 2, size - 2, Some(usize::MAX)).with_border(None);
+    for (k, (_, (i, j))) in bytes.iter().enumerate() {
+        let cell = &mut fallen[i + 1][j + 1];
+        *cell = (*cell).min(Some(k));
+    }
+
+    let free_after = |k| {
+        move |a: &Option<usize>, b: &Option<usize>| {
+            a.is_some_and(|a| a >= k) && b.is_some_and(|b| b >= k)
+        }
+    };
+    let mut regions = DisjointSet::from_grid(&fallen, free_after(bytes.len()));
+    let (start, end) = (
+        fallen.flat_index((1, 1)),
+        fallen.flat_index((size - 2, size - 2)),
+    );
+    if regions.same(start, end) {
+        return None;
+    }
+
+    // Remove the bytes from the last one, until the start and the end are connected again
+    (0..bytes.len())
+        .rev()
+        .find(|&k| {
+            let (_, (i, j)) = bytes[k];
+            if fallen[i + 1][j + 1] == Some(k) {
+                regions.union_matching_neighbours(&fallen, (i + 1, j + 1), free_after(k));
+            }
+            regions.same(start, end)
+        })
+        .map(|k| bytes[k].0)
+}
+
+/// Part 2 adding the bytes one by one, with a DFS to check whether the end is still reachable
+pub fn part2_dfs(input: &str) -> Result<&str, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_dfs_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
+
+pub fn part2_dfs_solve<'a>((bytes, size): (Vec<Byte<'a>>, usize)) -> Option<&'a str> {
+    (0..bytes.len())
+        .find(|i| !is_end_reachable(&bytes[..i + 1], size))
+        .map(|i| bytes[i].0)
 }
 
 #[cfg(test)]
@@ -174,27 +209,56 @@ mod tests {
 2,0
 "};
 
-    const EXAMPLE_SIZE: usize = 7 + 2;
-
     #[test]
-    fn example() {
-        assert_eq!(part1_inner::<EXAMPLE_SIZE>(EXAMPLE, 12), 22);
-        assert_eq!(part2_inner::<EXAMPLE_SIZE>(EXAMPLE), "6,1");
+    fn blocked() {
+        // The start is walled in by the first two bytes
+        let input = "1,0\n0,1\n";
+        assert_eq!(part1_example(input), Err(SolveError::NoAnswer));
+        assert_eq!(part1(input), Err(SolveError::NoAnswer));
+
+        // A byte falls right on the start or the end
+        assert_eq!(part1("0,0\n"), Err(SolveError::NoAnswer));
+        assert_eq!(part1_example("6,6\n"), Err(SolveError::NoAnswer));
+        assert_eq!(part2("0,0\n"), Ok("0,0"));
+        assert_eq!(part2_dfs("0,0\n"), Ok("0,0"));
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 294);
+    fn never_blocked() {
+        // A single byte cannot cut the end off the start
+        assert_eq!(part2("2,0\n"), Err(SolveError::NoAnswer));
+        assert_eq!(part2_dfs("2,0\n"), Err(SolveError::NoAnswer));
+        assert_eq!(part2_example("2,0\n"), Err(SolveError::NoAnswer));
+        assert_eq!(part2(""), Err(SolveError::NoAnswer));
+        assert_eq!(crate::solve(18, 2, "2,0\n"), Err(SolveError::NoAnswer));
     }
 
     #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, "31,22");
+    fn memory_space() {
+        // The memory space keeps its side when no byte falls on its last row or column
+        assert_eq!(part1("0,1\n"), Ok(140));
+        assert_eq!(part1_example("0,1\n"), Ok(12));
+        assert!(part1_example("7,0\n").is_err());
+        assert!(part1("70,71\n").is_err());
+        assert_eq!(
+            part1_example("1,2\n7,0\n").unwrap_err().to_string(),
+            "line 2 col 1: expected coordinates inside the 7x7 memory space"
+        );
+        assert_eq!(
+            part1_example("1,2,3\n").unwrap_err().to_string(),
+            "line 1 col 1: expected two numbers separated by `,`"
+        );
+    }
+
+    crate::utils::day_tests! {
+        day: 18,
+        examples: {
+            part1_example(EXAMPLE) => 22,
+            part2_example(EXAMPLE) => "6,1",
+        },
+        input: {
+            part1 => 294,
+            part2 => "31,22",
+        },
     }
 }
@@ -1,55 +1,72 @@
-use arrayvec::ArrayVec;
-use std::collections::HashMap;
+use tracing::info_span;
+
+use crate::{
+    utils::{Memo, ParseError},
+    SolveError,
+};
 
 type Num = u32;
 
-const TOWELS_SIZE: usize = 1000;
-const PATTERNS_SIZE: usize = 500;
+type Input<'a> = (Vec<&'a str>, Vec<&'a str>);
 
-pub fn parse(input: &str) -> (ArrayVec<&str, TOWELS_SIZE>, ArrayVec<&str, PATTERNS_SIZE>) {
+/// Towels and patterns are made of the stripe colors `w`, `u`, `b`, `r` and `g`
+fn check_stripes<'a>(input: &'a str, s: &'a str, expected: &str) -> Result<&'a str, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::at(input, s, expected));
+    }
+    match s.find(|c| !matches!(c, 'w' | 'u' | 'b' | 'r' | 'g')) {
+        Some(i) => Err(ParseError::at(input, &s[i..], "a stripe color")),
+        None => Ok(s),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Input<'_>, ParseError> {
     let mut lines = input.lines();
 
-    let towels = lines
-        .next()
-        .unwrap()
+    let line = lines.next().unwrap_or(input);
+    let towels = line
         .split(", ")
-        .collect::<ArrayVec<_, TOWELS_SIZE>>();
+        .map(|towel| check_stripes(input, towel, "a towel"))
+        .collect::<Result<_, _>>()?;
+
+    if let Some(line) = lines.next().filter(|line| !line.is_empty()) {
+        return Err(ParseError::at(
+            input,
+            line,
+            "an empty line after the towels",
+        ));
+    }
 
-    lines.next();
+    let patterns = lines
+        .map(|pattern| check_stripes(input, pattern, "a pattern"))
+        .collect::<Result<_, _>>()?;
 
-    (towels, lines.collect())
+    Ok((towels, patterns))
 }
 
-pub fn part1(input: &str) -> Num {
-    let (towels, patterns) = parse(input);
-
-    fn is_solvable<'a>(
-        pattern: &'a str,
-        towels: &ArrayVec<&str, TOWELS_SIZE>,
-        cache: &mut HashMap<&'a str, bool>,
-    ) -> bool {
-        if let Some(&result) = cache.get(pattern) {
-            return result;
-        }
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(parsed)))
+}
 
+pub fn part1_solve((towels, patterns): Input) -> Num {
+    fn is_solvable<'a>(pattern: &'a str, towels: &[&str], cache: &mut Memo<&'a str, bool>) -> bool {
         if pattern.is_empty() {
             return true;
         }
 
-        let ret = towels.iter().any(|towel| {
-            if towel.len() > pattern.len() {
-                return false;
-            }
-
-            pattern.starts_with(towel) && is_solvable(&pattern[towel.len()..], towels, cache)
-        });
-
-        cache.insert(pattern, ret);
+        cache.get_or_compute(pattern, |cache| {
+            towels.iter().any(|towel| {
+                if towel.len() > pattern.len() {
+                    return false;
+                }
 
-        ret
+                pattern.starts_with(towel) && is_solvable(&pattern[towel.len()..], towels, cache)
+            })
+        })
     }
 
-    let mut cache = HashMap::new();
+    let mut cache = Memo::new();
     patterns
         .iter()
         .map(|pattern| {
@@ -62,35 +79,41 @@ pub fn part1(input: &str) -> Num {
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-    let (towels, patterns) = parse(input);
+pub fn part2(input: &str) -> Result<u128, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
+}
 
-    fn count_combinations(pattern: &str, towels: &ArrayVec<&str, TOWELS_SIZE>) -> usize {
-        let mut dp = vec![0; pattern.len() + 1];
+/// Number of arrangements of all the patterns, `None` when it does not fit in a `u128`
+pub fn part2_solve((towels, patterns): Input) -> Option<u128> {
+    fn count_combinations(pattern: &str, towels: &[&str]) -> Option<u128> {
+        let mut dp = vec![0_u128; pattern.len() + 1];
         dp[0] = 1;
 
         for i in 0..pattern.len() {
             if dp[i] > 0 {
                 for towel in towels.iter() {
                     if i + towel.len() <= pattern.len() && &pattern[i..i + towel.len()] == *towel {
-                        dp[i + towel.len()] += dp[i];
+                        dp[i + towel.len()] = dp[i + towel.len()].checked_add(dp[i])?;
                     }
                 }
             }
         }
 
-        dp[pattern.len()]
+        Some(dp[pattern.len()])
     }
 
-    patterns
-        .iter()
-        .map(|pattern| count_combinations(pattern, &towels))
-        .sum()
+    patterns.iter().try_fold(0_u128, |total, pattern| {
+        total.checked_add(count_combinations(pattern, &towels)?)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     const EXAMPLE: &str = indoc::indoc! {"
 r, wr, b, g, bwu, rb, gb, br
@@ -105,25 +128,53 @@ brgr
 bbrgwb
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 6);
-        assert_eq!(part2(EXAMPLE), 16);
+    crate::utils::day_tests! {
+        day: 19,
+        examples: {
+            part1(EXAMPLE) => 6,
+            part2(EXAMPLE) => 16,
+        },
+        input: {
+            part1 => 327,
+            part2 => 772696486795255,
+        },
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 327);
+    fn too_many_arrangements() {
+        // Fibonacci numbers, the 300th does not fit in a `u128`
+        let input = format!("r, rr\n\n{}\n{}\n", "r".repeat(150), "r".repeat(299));
+        assert_eq!(part1(&input), Ok(2));
+        assert_eq!(part2(&input), Err(SolveError::NoAnswer));
     }
 
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 772696486795255);
+    /// Every arrangement of the towels, one at a time
+    fn arrangements(pattern: &str, towels: &[String]) -> usize {
+        if pattern.is_empty() {
+            return 1;
+        }
+
+        towels
+            .iter()
+            .filter_map(|towel| pattern.strip_prefix(towel.as_str()))
+            .map(|rest| arrangements(rest, towels))
+            .sum()
+    }
+
+    proptest! {
+        #[test]
+        fn enumeration(
+            towels in prop::collection::vec("[wubrg]{1,3}", 1..8),
+            patterns in prop::collection::vec("[wubrg]{1,12}", 1..5),
+        ) {
+            let input = format!("{}\n\n{}\n", towels.join(", "), patterns.join("\n"));
+            let counts = patterns
+                .iter()
+                .map(|pattern| arrangements(pattern, &towels))
+                .collect::<Vec<_>>();
+
+            prop_assert_eq!(part1(&input), Ok(counts.iter().filter(|&&count| count > 0).count() as Num));
+            prop_assert_eq!(part2(&input), Ok(counts.iter().sum::<usize>() as u128));
+        }
     }
 }
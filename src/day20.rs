@@ -1,155 +1,104 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
+use tracing::info_span;
 
-use crate::utils::{CoordinateAdd, ManhattanDistance, CARDINAL_DIRECTIONS};
+use crate::{
+    utils::{bfs, grid_dimensions, par_sum, Direction, Grid, ManhattanDistance, ParseError, Point},
+    SolveError,
+};
 
 type Num = u32;
 
-#[derive(Debug, PartialEq, Eq)]
-struct State {
-    i: usize,
-    j: usize,
-    cost: Num,
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Comparison is flipped to make the heap pop the smallest cost first
-        other.cost.cmp(&self.cost)
-    }
-}
-
-struct Input<const SIZE: usize> {
-    grid: [[u8; SIZE]; SIZE],
+/// Racetrack surrounded by walls
+pub struct Input {
+    grid: Grid<u8>,
     start: (usize, usize),
     end: (usize, usize),
 }
 
-fn parse_input<const SIZE: usize>(input: &str) -> Input<SIZE> {
-    let mut grid = [[b'.'; SIZE]; SIZE];
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let (height, width) = grid_dimensions(input)?;
     let mut start = None;
     let mut end = None;
 
-    for i in 0..SIZE {
-        grid[i][0] = b'%';
-        grid[i][SIZE - 1] = b'%';
-        grid[0][i] = b'%';
-        grid[SIZE - 1][i] = b'%';
-    }
-
-    input.lines().enumerate().for_each(|(i, l)| {
-        l.bytes().enumerate().for_each(|(j, b)| {
-            if b == b'S' {
-                start = Some((i + 1, j + 1));
-            } else if b == b'E' {
-                end = Some((i + 1, j + 1));
-            }
-            grid[i + 1][j + 1] = b;
-        });
-    });
+    let grid = Grid::parse(input, |(i, j), b| {
+        let is_border = i == 0 || i == height - 1 || j == 0 || j == width - 1;
+        match b {
+            b'#' => {}
+            _ if is_border => return Err("a wall `#`"),
+            b'.' => {}
+            b'S' if start.is_none() => start = Some((i, j)),
+            b'E' if end.is_none() => end = Some((i, j)),
+            _ => return Err("`#`, `.`, a single `S` or a single `E`"),
+        }
+        Ok(b)
+    })?;
 
-    Input {
+    Ok(Input {
         grid,
-        start: start.unwrap(),
-        end: end.unwrap(),
-    }
+        start: start.ok_or_else(|| ParseError::end(input, "a start `S`"))?,
+        end: end.ok_or_else(|| ParseError::end(input, "an end `E`"))?,
+    })
 }
 
-fn part1_inner<const SIZE: usize>(input: &str, threshold: Num) -> Num {
-    let Input { grid, start, end } = parse_input::<SIZE>(input);
-    let mut queue = BinaryHeap::with_capacity(SIZE * SIZE);
-
-    let mut paths_to_end = [[Num::MAX; SIZE]; SIZE];
-
-    queue.push(State {
-        i: end.0,
-        j: end.1,
-        cost: 0,
-    });
-
-    while let Some(u) = queue.pop() {
-        if paths_to_end[u.i][u.j] <= u.cost {
-            // There is already a better path for this node
-            continue;
-        }
-        paths_to_end[u.i][u.j] = u.cost;
-
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((u.i, u.j));
-
-                if grid[i][j] == b'#' {
-                    return;
-                }
-
-                queue.push(State {
-                    i,
-                    j,
-                    cost: u.cost + 1,
-                });
-            });
-    }
-
-    let mut paths_from_start = [[Num::MAX; SIZE]; SIZE];
-
-    queue.push(State {
-        i: start.0,
-        j: start.1,
-        cost: 0,
+/// Length of the shortest path from `from` to each cell of the track, `Num::MAX` for the walls
+fn distances(grid: &Grid<u8>, from: (usize, usize)) -> Grid<Num> {
+    let search = bfs(
+        [from],
+        |position| grid.neighbours(position).filter(|&next| grid[next] != b'#'),
+        |_| false,
+    );
+
+    let mut distances = Grid::new(grid.height(), grid.width(), Num::MAX);
+    search.costs().for_each(|(position, cost)| {
+        distances[position] = cost as Num;
     });
 
-    while let Some(u) = queue.pop() {
-        if paths_from_start[u.i][u.j] <= u.cost {
-            // There is already a better path for this node
-            continue;
-        }
-        paths_from_start[u.i][u.j] = u.cost;
+    distances
+}
 
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((u.i, u.j));
+/// Cheats of 2 picoseconds that save at least `threshold` picoseconds, `None` when the end cannot
+/// be reached without cheating
+fn part1_inner(Input { grid, start, end }: Input, threshold: Num) -> Option<Num> {
+    let (rows, columns) = (grid.height(), grid.width());
 
-                if grid[i][j] == b'#' {
-                    return;
-                }
-
-                queue.push(State {
-                    i,
-                    j,
-                    cost: u.cost + 1,
-                });
-            });
-    }
+    let bfs_span = info_span!("bfs").entered();
+    let paths_to_end = distances(&grid, end);
+    let paths_from_start = distances(&grid, start);
+    bfs_span.exit();
 
+    let _cheats_span = info_span!("cheats").entered();
     let best_path_without_cheats = paths_from_start[end.0][end.1];
+    if best_path_without_cheats == Num::MAX {
+        return None;
+    }
 
     // Look for all the cheats and how much time they save
     let mut ret = 0;
-    for i in 2..SIZE - 1 {
-        for j in 2..SIZE - 1 {
+    for i in 1..rows - 1 {
+        for j in 1..columns - 1 {
             // Look for cheats
             match grid[i][j] {
-                b'#' | b'%' | b'E' => {}
+                b'#' | b'E' => {}
                 _ => {
-                    ret += CARDINAL_DIRECTIONS
-                        .iter()
+                    ret += Direction::ALL
+                        .into_iter()
                         .map(|direction| {
-                            let (i1, j1) = direction.coord_add((i, j));
-                            let (i2, j2) = direction.coord_add((i1, j1));
+                            let Some((i2, j2)) = grid.step((i, j), Point::from(direction) * 2)
+                            else {
+                                return 0;
+                            };
 
-                            if grid[i2][j2] == b'#' || grid[i2][j2] == b'%' {
+                            if grid[i2][j2] == b'#' {
                                 // Cannot cheat from here
                                 return 0;
                             }
 
-                            let cost_with_cheat = paths_from_start[i][j] + 2 + paths_to_end[i2][j2];
+                            // Parts of the track can be cut off from the start or the end
+                            let Some(cost_with_cheat) = paths_from_start[i][j]
+                                .checked_add(2)
+                                .and_then(|cost| cost.checked_add(paths_to_end[i2][j2]))
+                            else {
+                                return 0;
+                            };
 
                             let saved = best_path_without_cheats.saturating_sub(cost_with_cheat);
 
@@ -165,124 +114,88 @@ fn part1_inner<const SIZE: usize>(input: &str, threshold: Num) -> Num {
         }
     }
 
-    ret
+    Some(ret)
 }
 
-const INPUT_SIZE: usize = 143;
-pub fn part1(input: &str) -> Num {
-    part1_inner::<INPUT_SIZE>(input, 100)
+pub fn part1(input: &str) -> Result<Num, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part1_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-fn part2_inner<const SIZE: usize>(input: &str, threshold: Num) -> Num {
-    let Input { grid, start, end } = parse_input::<SIZE>(input);
-    let mut queue = BinaryHeap::with_capacity(SIZE * SIZE);
-
-    let mut paths_to_end = [[Num::MAX; SIZE]; SIZE];
-
-    queue.push(State {
-        i: end.0,
-        j: end.1,
-        cost: 0,
-    });
-
-    while let Some(u) = queue.pop() {
-        if paths_to_end[u.i][u.j] <= u.cost {
-            // There is already a better path for this node
-            continue;
-        }
-        paths_to_end[u.i][u.j] = u.cost;
-
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((u.i, u.j));
-
-                if grid[i][j] == b'#' {
-                    return;
-                }
-
-                queue.push(State {
-                    i,
-                    j,
-                    cost: u.cost + 1,
-                });
-            });
-    }
-
-    let mut paths_from_start = [[Num::MAX; SIZE]; SIZE];
-
-    queue.push(State {
-        i: start.0,
-        j: start.1,
-        cost: 0,
-    });
-
-    while let Some(u) = queue.pop() {
-        if paths_from_start[u.i][u.j] <= u.cost {
-            // There is already a better path for this node
-            continue;
-        }
-        paths_from_start[u.i][u.j] = u.cost;
-
-        [(-1, 0), (1, 0), (0, -1), (0, 1)]
-            .iter()
-            .for_each(|direction| {
-                let (i, j) = direction.coord_add((u.i, u.j));
-
-                if grid[i][j] == b'#' {
-                    return;
-                }
+pub fn part1_solve(parsed: Input) -> Option<Num> {
+    part1_inner(parsed, 100)
+}
 
-                queue.push(State {
-                    i,
-                    j,
-                    cost: u.cost + 1,
-                });
-            });
+/// Cheats of up to `radius` picoseconds that save at least `threshold` picoseconds, `None` when
+/// the end cannot be reached without cheating
+pub fn count_cheats(
+    Input { grid, start, end }: Input,
+    radius: usize,
+    threshold: Num,
+) -> Option<Num> {
+    let (rows, columns) = (grid.height(), grid.width());
+
+    let bfs_span = info_span!("bfs").entered();
+    let paths_to_end = distances(&grid, end);
+    let paths_from_start = distances(&grid, start);
+    bfs_span.exit();
+
+    let _cheats_span = info_span!("cheats").entered();
+    let best_path_without_cheats = paths_from_start[end.0][end.1];
+    if best_path_without_cheats == Num::MAX {
+        return None;
     }
 
-    let best_path_without_cheats = paths_from_start[end.0][end.1];
+    // Cheats start from any cell of the track but the end
+    let track = grid
+        .cells()
+        .filter(|&(_, &b)| !matches!(b, b'#' | b'E'))
+        .map(|(position, _)| position)
+        .collect::<Vec<_>>();
 
     // Look for all the cheats and how much time they save
-    let mut ret = 0;
-    for i in 2..SIZE - 1 {
-        for j in 2..SIZE - 1 {
-            // Look for cheats
-            match grid[i][j] {
-                b'#' | b'%' | b'E' => {}
-                _ => {
-                    ret += (i, j)
-                        .within_manhattan_distance(20, 0, SIZE)
-                        .map(|(end_i, end_j)| {
-                            if grid[end_i][end_j] == b'#' || grid[end_i][end_j] == b'%' {
-                                // Cannot cheat from here
-                                return 0;
-                            }
+    Some(par_sum(&track, |&(i, j)| {
+        (i, j)
+            .within_manhattan_distance(radius, (0..rows, 0..columns))
+            // The first position is the start of the cheat itself
+            .skip(1)
+            .map(|(end_i, end_j)| {
+                if grid[end_i][end_j] == b'#' {
+                    // Cannot cheat from here
+                    return 0;
+                }
 
-                            let cost_with_cheat = paths_from_start[i][j]
-                                + end_i.abs_diff(i) as u32
-                                + end_j.abs_diff(j) as u32
-                                + paths_to_end[end_i][end_j];
+                let cheat = (end_i.abs_diff(i) + end_j.abs_diff(j)) as Num;
+                let Some(cost_with_cheat) = paths_from_start[i][j]
+                    .checked_add(cheat)
+                    .and_then(|cost| cost.checked_add(paths_to_end[end_i][end_j]))
+                else {
+                    return 0;
+                };
 
-                            let saved = best_path_without_cheats.saturating_sub(cost_with_cheat);
+                let saved = best_path_without_cheats.saturating_sub(cost_with_cheat);
 
-                            if saved >= threshold {
-                                1
-                            } else {
-                                0
-                            }
-                        })
-                        .sum::<Num>();
+                if saved >= threshold {
+                    1
+                } else {
+                    0
                 }
-            };
-        }
-    }
+            })
+            .sum::<Num>()
+    }))
+}
 
-    ret
+pub fn part2(input: &str) -> Result<Num, SolveError> {
+    let parsed = info_span!("parse").in_scope(|| parse(input))?;
+    info_span!("solve")
+        .in_scope(|| part2_solve(parsed))
+        .ok_or(SolveError::NoAnswer)
 }
 
-pub fn part2(input: &str) -> Num {
-    part2_inner::<INPUT_SIZE>(input, 100)
+pub fn part2_solve(parsed: Input) -> Option<Num> {
+    count_cheats(parsed, 20, 100)
 }
 
 #[cfg(test)]
@@ -307,32 +220,36 @@ mod tests {
 ###############
 "};
 
-    const EXAMPLE_SIZE: usize = 15 + 2;
-
     #[test]
     fn example() {
-        assert_eq!(part1_inner::<EXAMPLE_SIZE>(EXAMPLE, 12), 8);
+        assert_eq!(part1_inner(parse(EXAMPLE).unwrap(), 12), Some(8));
         assert_eq!(
-            part2_inner::<EXAMPLE_SIZE>(EXAMPLE, 50),
-            [32, 31, 29, 39, 25, 23, 20, 19, 12, 14, 12, 22, 4, 3]
-                .iter()
-                .sum()
+            count_cheats(parse(EXAMPLE).unwrap(), 20, 50),
+            Some(
+                [32, 31, 29, 39, 25, 23, 20, 19, 12, 14, 12, 22, 4, 3]
+                    .iter()
+                    .sum::<Num>()
+            )
         );
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 1365);
+    fn cut_off_track() {
+        let input = "#######\n#S#.#E#\n#######\n";
+        assert_eq!(part1(input), Err(SolveError::NoAnswer));
+        assert_eq!(part2(input), Err(SolveError::NoAnswer));
+
+        // Cheats can reach the cut off part, which leads nowhere
+        let input = "#########\n#S.....E#\n#########\n#.......#\n#########\n";
+        assert_eq!(part1_inner(parse(input).unwrap(), 1), Some(0));
+        assert_eq!(count_cheats(parse(input).unwrap(), 20, 1), Some(0));
     }
 
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 986082);
+    crate::utils::day_tests! {
+        day: 20,
+        input: {
+            part1 => 1365,
+            part2 => 986082,
+        },
     }
 }
@@ -1,4 +1,7 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::cmp::Ordering;
+use tracing::info_span;
+
+use crate::utils::{parse_number, Direction, Memo, ParseError};
 
 type Num = usize;
 
@@ -18,43 +21,58 @@ const NUMERIC_KEYPAD: [[Option<u8>; 3]; 4] = [
     [None, Some(b'0'), Some(b'A')],
 ];
 
-fn get_keypad_path(from: u8, to: u8) -> Vec<Vec<u8>> {
+/// Key of the directional keypad, a move or `A` to press the key the arm points at
+///     +---+---+
+///     | ^ | A |
+/// +---+---+---+
+/// | < | v | > |
+/// +---+---+---+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Move(Direction),
+    Press,
+}
+
+fn get_keypad_path(from: Key, to: Key) -> Vec<Vec<Direction>> {
+    use Direction::{East, North, South, West};
+    use Key::{Move, Press};
+
     match (from, to) {
         // Starting from `<`
-        (b'<', b'v') => vec![vec![b'>']],
-        (b'<', b'>') => vec![vec![b'>', b'>']],
-        (b'<', b'^') => vec![vec![b'>', b'^']],
-        (b'<', b'A') => vec![vec![b'>', b'>', b'^']],
+        (Move(West), Move(South)) => vec![vec![East]],
+        (Move(West), Move(East)) => vec![vec![East, East]],
+        (Move(West), Move(North)) => vec![vec![East, North]],
+        (Move(West), Press) => vec![vec![East, East, North]],
 
         // Starting from `v`
-        (b'v', b'<') => vec![vec![b'<']],
-        (b'v', b'>') => vec![vec![b'>']],
-        (b'v', b'^') => vec![vec![b'^']],
-        (b'v', b'A') => vec![vec![b'>', b'^'], vec![b'^', b'>']],
+        (Move(South), Move(West)) => vec![vec![West]],
+        (Move(South), Move(East)) => vec![vec![East]],
+        (Move(South), Move(North)) => vec![vec![North]],
+        (Move(South), Press) => vec![vec![East, North], vec![North, East]],
 
         // Starting from `^`
-        (b'^', b'<') => vec![vec![b'v', b'<']],
-        (b'^', b'>') => vec![vec![b'>', b'v'], vec![b'v', b'>']],
-        (b'^', b'v') => vec![vec![b'v']],
-        (b'^', b'A') => vec![vec![b'>']],
+        (Move(North), Move(West)) => vec![vec![South, West]],
+        (Move(North), Move(East)) => vec![vec![East, South], vec![South, East]],
+        (Move(North), Move(South)) => vec![vec![South]],
+        (Move(North), Press) => vec![vec![East]],
 
-        // Starting from `<`
-        (b'>', b'<') => vec![vec![b'<', b'<']],
-        (b'>', b'^') => vec![vec![b'<', b'^'], vec![b'^', b'<']],
-        (b'>', b'v') => vec![vec![b'<']],
-        (b'>', b'A') => vec![vec![b'^']],
+        // Starting from `>`
+        (Move(East), Move(West)) => vec![vec![West, West]],
+        (Move(East), Move(North)) => vec![vec![West, North], vec![North, West]],
+        (Move(East), Move(South)) => vec![vec![West]],
+        (Move(East), Press) => vec![vec![North]],
 
         // Starting from `A`
-        (b'A', b'^') => vec![vec![b'<']],
-        (b'A', b'v') => vec![vec![b'<', b'v'], vec![b'v', b'<']],
-        (b'A', b'>') => vec![vec![b'v']],
-        (b'A', b'<') => vec![vec![b'v', b'<', b'<']],
+        (Press, Move(North)) => vec![vec![West]],
+        (Press, Move(South)) => vec![vec![West, South], vec![South, West]],
+        (Press, Move(East)) => vec![vec![South]],
+        (Press, Move(West)) => vec![vec![South, West, West]],
 
         _ => vec![],
     }
 }
 
-fn get_numeric_keypad_path(from: u8, to: u8) -> Vec<Vec<u8>> {
+fn get_numeric_keypad_path(from: u8, to: u8) -> Vec<Vec<Direction>> {
     if from == to {
         return Vec::new();
     }
@@ -89,14 +107,14 @@ fn get_numeric_keypad_path(from: u8, to: u8) -> Vec<Vec<u8>> {
     };
 
     let horizontal_path = match j_to.cmp(&j_from) {
-        Ordering::Less => Some(vec![b'<'; j_from - j_to]),
-        Ordering::Greater => Some(vec![b'>'; j_to - j_from]),
+        Ordering::Less => Some(vec![Direction::West; j_from - j_to]),
+        Ordering::Greater => Some(vec![Direction::East; j_to - j_from]),
         Ordering::Equal => None,
     };
 
     let vertical_path = match i_to.cmp(&i_from) {
-        Ordering::Less => Some(vec![b'^'; i_from - i_to]),
-        Ordering::Greater => Some(vec![b'v'; i_to - i_from]),
+        Ordering::Less => Some(vec![Direction::North; i_from - i_to]),
+        Ordering::Greater => Some(vec![Direction::South; i_to - i_from]),
         Ordering::Equal => None,
     };
 
@@ -138,54 +156,50 @@ fn get_numeric_keypad_path(from: u8, to: u8) -> Vec<Vec<u8>> {
 }
 
 fn get_shortest_directional_sequence(
-    keys: &[u8],
+    keys: &[Key],
     depth: usize,
-    cache: &mut HashMap<(Vec<u8>, usize), usize>,
+    cache: &mut Memo<(Vec<Key>, usize), usize>,
 ) -> usize {
     if depth == 0 {
         return keys.len();
     }
 
-    if let Some(len) = cache.get(&(keys.to_vec(), depth)) {
-        return *len;
-    }
-
-    let mut total = 0;
-    // Cut up the sequence between segments separated by A presses and recursively look for
-    // shortest sequence for each one
-    let sub_directionnal_sequences = keys.split_inclusive(|k| *k == b'A');
-
-    for sub_directionnal_sequence in sub_directionnal_sequences {
-        let mut sequences = Vec::new();
-
-        build_sequence_directional(
-            sub_directionnal_sequence,
-            0,
-            b'A',
-            Vec::new(),
-            &mut sequences,
-        );
+    cache.get_or_compute((keys.to_vec(), depth), |cache| {
+        let mut total = 0;
+        // Cut up the sequence between segments separated by A presses and recursively look for
+        // shortest sequence for each one
+        let sub_directionnal_sequences = keys.split_inclusive(|k| *k == Key::Press);
+
+        for sub_directionnal_sequence in sub_directionnal_sequences {
+            let mut sequences = Vec::new();
+
+            build_sequence_directional(
+                sub_directionnal_sequence,
+                0,
+                Key::Press,
+                Vec::new(),
+                &mut sequences,
+            );
+
+            let mut min = usize::MAX;
+            for sequence in sequences.iter() {
+                let len = get_shortest_directional_sequence(sequence, depth - 1, cache);
+                min = len.min(min);
+            }
 
-        let mut min = usize::MAX;
-        for sequence in sequences.iter() {
-            let len = get_shortest_directional_sequence(sequence, depth - 1, cache);
-            min = len.min(min);
+            total += min;
         }
 
-        total += min;
-    }
-
-    cache.insert((keys.to_vec(), depth), total);
-
-    total
+        total
+    })
 }
 
 fn build_sequence_directional(
-    keys: &[u8],
+    keys: &[Key],
     index: usize,
-    previous_key: u8,
-    mut current_path: Vec<u8>,
-    result: &mut Vec<Vec<u8>>,
+    previous_key: Key,
+    mut current_path: Vec<Key>,
+    result: &mut Vec<Vec<Key>>,
 ) {
     if index == keys.len() {
         result.push(current_path);
@@ -195,15 +209,15 @@ fn build_sequence_directional(
     let paths = get_keypad_path(previous_key, keys[index]);
 
     if paths.is_empty() {
-        current_path.push(b'A');
+        current_path.push(Key::Press);
         build_sequence_directional(keys, index + 1, keys[index], current_path, result);
         return;
     }
 
     for path in paths {
         let mut new_path = current_path.clone();
-        new_path.extend_from_slice(&path);
-        new_path.push(b'A');
+        new_path.extend(path.into_iter().map(Key::Move));
+        new_path.push(Key::Press);
         build_sequence_directional(keys, index + 1, keys[index], new_path, result);
     }
 }
@@ -212,8 +226,8 @@ fn build_sequence_numeric(
     keys: &[u8],
     index: usize,
     previous_key: u8,
-    mut current_path: Vec<u8>,
-    result: &mut Vec<Vec<u8>>,
+    mut current_path: Vec<Key>,
+    result: &mut Vec<Vec<Key>>,
 ) {
     if index == keys.len() {
         result.push(current_path);
@@ -223,63 +237,83 @@ fn build_sequence_numeric(
     let paths = get_numeric_keypad_path(previous_key, keys[index]);
 
     if paths.is_empty() {
-        current_path.push(b'A');
-        build_sequence_directional(keys, index + 1, keys[index], current_path, result);
+        current_path.push(Key::Press);
+        build_sequence_numeric(keys, index + 1, keys[index], current_path, result);
         return;
     }
 
     for path in paths {
         let mut new_path = current_path.clone();
-        new_path.extend_from_slice(&path);
-        new_path.push(b'A');
+        new_path.extend(path.into_iter().map(Key::Move));
+        new_path.push(Key::Press);
         build_sequence_numeric(keys, index + 1, keys[index], new_path, result);
     }
 }
 
-pub fn part1(input: &str) -> Num {
+/// Codes are three digits followed by `A`
+pub fn parse(input: &str) -> Result<Vec<(Num, &[u8])>, ParseError> {
     input
         .lines()
         .map(|l| {
-            let keys = l.as_bytes();
-            let number_to_type = l[..l.len() - 1].parse::<usize>().unwrap();
-            (number_to_type, keys)
+            let digits = l
+                .strip_suffix('A')
+                .ok_or_else(|| ParseError::at(input, &l[l.len()..], "`A`"))?;
+            if let Some(i) = digits.find(|c: char| !c.is_ascii_digit()) {
+                return Err(ParseError::at(input, &digits[i..], "a digit"));
+            }
+            if digits.len() != 3 {
+                return Err(ParseError::at(input, l, "three digits followed by `A`"));
+            }
+
+            Ok((parse_number(input, digits)?, l.as_bytes()))
         })
-        .fold(0, |acc, (number_to_type, keys)| {
-            let mut keys_directionals = Vec::new();
+        .collect()
+}
+
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let codes = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(codes)))
+}
 
-            build_sequence_numeric(keys, 0, b'A', Vec::new(), &mut keys_directionals);
+pub fn part1_solve(codes: Vec<(Num, &[u8])>) -> Num {
+    // The shortest sequences do not depend on the code
+    let mut cache = Memo::new();
+    codes.into_iter().fold(0, |acc, (number_to_type, keys)| {
+        let mut keys_directionals = Vec::new();
 
-            let min_sequence_len = keys_directionals
-                .into_iter()
-                .map(|k| get_shortest_directional_sequence(&k, 2, &mut HashMap::new()))
-                .min()
-                .unwrap();
+        build_sequence_numeric(keys, 0, b'A', Vec::new(), &mut keys_directionals);
 
-            acc + min_sequence_len * number_to_type
-        })
+        let min_sequence_len = keys_directionals
+            .into_iter()
+            .map(|k| get_shortest_directional_sequence(&k, 2, &mut cache))
+            .min()
+            .unwrap();
+
+        acc + min_sequence_len * number_to_type
+    })
 }
 
-pub fn part2(input: &str) -> Num {
-    input
-        .lines()
-        .map(|l| {
-            let keys = l.as_bytes();
-            let number_to_type = l[..l.len() - 1].parse::<usize>().unwrap();
-            (number_to_type, keys)
-        })
-        .fold(0, |acc, (number_to_type, keys)| {
-            let mut keys_directionals = Vec::new();
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let codes = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(codes)))
+}
 
-            build_sequence_numeric(keys, 0, b'A', Vec::new(), &mut keys_directionals);
+pub fn part2_solve(codes: Vec<(Num, &[u8])>) -> Num {
+    // The shortest sequences do not depend on the code
+    let mut cache = Memo::new();
+    codes.into_iter().fold(0, |acc, (number_to_type, keys)| {
+        let mut keys_directionals = Vec::new();
 
-            let min_sequence_len = keys_directionals
-                .into_iter()
-                .map(|k| get_shortest_directional_sequence(&k, 25, &mut HashMap::new()))
-                .min()
-                .unwrap();
+        build_sequence_numeric(keys, 0, b'A', Vec::new(), &mut keys_directionals);
 
-            acc + min_sequence_len * number_to_type
-        })
+        let min_sequence_len = keys_directionals
+            .into_iter()
+            .map(|k| get_shortest_directional_sequence(&k, 25, &mut cache))
+            .min()
+            .unwrap();
+
+        acc + min_sequence_len * number_to_type
+    })
 }
 
 #[cfg(test)]
@@ -294,75 +328,84 @@ mod tests {
 379A
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 126384);
-        assert_eq!(part2(EXAMPLE), 154115708116294);
+    crate::utils::day_tests! {
+        day: 21,
+        examples: {
+            part1(EXAMPLE) => 126384,
+            part2(EXAMPLE) => 154115708116294,
+            // A repeated digit is pressed again without moving
+            part1("622A\n") => 32344,
+            part2("622A\n") => 40525482064324,
+        },
+        input: {
+            part1 => 205160,
+            part2 => 252473394928452,
+        },
     }
 
     #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 205160);
+    fn malformed() {
+        assert_eq!(
+            part1("029A\n945902902A\n").unwrap_err().to_string(),
+            "line 2 col 1: expected three digits followed by `A`"
+        );
+        assert_eq!(
+            part2("029A\n9x0A\n").unwrap_err().to_string(),
+            "line 2 col 2: expected a digit"
+        );
     }
 
-    #[test]
-    fn run_part2() {
-        let input = crate::utils::get_day_input!();
-        let output = part2(&input);
-        println!("Part 2: {}", output);
-        assert_eq!(output, 252473394928452);
+    /// Moves of `^>v<` arrows
+    fn moves(arrows: &[u8]) -> Vec<Direction> {
+        arrows
+            .iter()
+            .map(|&arrow| Direction::from_arrow(arrow).unwrap())
+            .collect()
+    }
+
+    /// Keys of arrows and `A`
+    fn keys(arrows: &[u8]) -> Vec<Key> {
+        arrows
+            .iter()
+            .map(|&arrow| Direction::from_arrow(arrow).map_or(Key::Press, Key::Move))
+            .collect()
     }
 
     #[test]
     fn test_get_numeric_keypad_path() {
         assert_eq!(get_numeric_keypad_path(b'1', b'1').len(), 0);
-        assert_eq!(get_numeric_keypad_path(b'1', b'3'), vec![vec![b'>', b'>']]);
+        assert_eq!(get_numeric_keypad_path(b'1', b'3'), vec![moves(b">>")]);
         assert_eq!(
             get_numeric_keypad_path(b'A', b'5'),
-            vec![vec![b'^', b'^', b'<'], vec![b'<', b'^', b'^']]
+            vec![moves(b"^^<"), moves(b"<^^")]
         );
         assert_eq!(
             get_numeric_keypad_path(b'5', b'A'),
-            vec![vec![b'v', b'v', b'>'], vec![b'>', b'v', b'v']]
-        );
-        assert_eq!(
-            get_numeric_keypad_path(b'A', b'4'),
-            vec![vec![b'^', b'^', b'<', b'<']]
-        );
-        assert_eq!(
-            get_numeric_keypad_path(b'4', b'A'),
-            vec![vec![b'>', b'>', b'v', b'v']]
+            vec![moves(b"vv>"), moves(b">vv")]
         );
+        assert_eq!(get_numeric_keypad_path(b'A', b'4'), vec![moves(b"^^<<")]);
+        assert_eq!(get_numeric_keypad_path(b'4', b'A'), vec![moves(b">>vv")]);
     }
 
     #[test]
     fn test_build_sequence_directional() {
         let mut sequences = Vec::new();
-        build_sequence_directional(b"<A", 0, b'A', Vec::new(), &mut sequences);
-        assert_eq!(
-            sequences,
-            vec![vec![b'v', b'<', b'<', b'A', b'>', b'>', b'^', b'A']]
-        );
+        build_sequence_directional(&keys(b"<A"), 0, Key::Press, Vec::new(), &mut sequences);
+        assert_eq!(sequences, vec![keys(b"v<<A>>^A")]);
 
         let mut sequences = Vec::new();
-        build_sequence_directional(b"v<<A", 0, b'A', Vec::new(), &mut sequences);
-        assert_eq!(
-            sequences,
-            vec![b"<vA<AA>>^A".to_vec(), b"v<A<AA>>^A".to_vec()]
-        );
+        build_sequence_directional(&keys(b"v<<A"), 0, Key::Press, Vec::new(), &mut sequences);
+        assert_eq!(sequences, vec![keys(b"<vA<AA>>^A"), keys(b"v<A<AA>>^A")]);
     }
 
     #[test]
     fn test_get_shortest_directional_sequence() {
         assert_eq!(
-            get_shortest_directional_sequence(b"<A", 1, &mut HashMap::new()),
+            get_shortest_directional_sequence(&keys(b"<A"), 1, &mut Memo::new()),
             8
         );
         assert_eq!(
-            get_shortest_directional_sequence(b"<A", 2, &mut HashMap::new()),
+            get_shortest_directional_sequence(&keys(b"<A"), 2, &mut Memo::new()),
             18
         );
     }
@@ -374,18 +417,19 @@ mod tests {
         assert_eq!(
             sequences,
             vec![
-                b"^<A^>A".to_vec(),
-                b"^<A>^A".to_vec(),
-                b"<^A^>A".to_vec(),
-                b"<^A>^A".to_vec()
+                keys(b"^<A^>A"),
+                keys(b"^<A>^A"),
+                keys(b"<^A^>A"),
+                keys(b"<^A>^A")
             ]
         );
 
+        // The keys after a repeated digit still need moves
         let mut sequences = Vec::new();
-        build_sequence_directional(b"v<<A", 0, b'A', Vec::new(), &mut sequences);
+        build_sequence_numeric(b"119A", 0, b'A', Vec::new(), &mut sequences);
         assert_eq!(
             sequences,
-            vec![b"<vA<AA>>^A".to_vec(), b"v<A<AA>>^A".to_vec()]
+            vec![keys(b"^<<AA^^>>AvvvA"), keys(b"^<<AA>>^^AvvvA")]
         );
     }
 }
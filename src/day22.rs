@@ -1,4 +1,7 @@
 use std::{collections::HashMap, iter};
+use tracing::info_span;
+
+use crate::utils::{par_fold, par_sum, parse_number, ParseError};
 
 type Num = u64;
 
@@ -12,59 +15,81 @@ fn next_secret(secret: Num) -> Num {
     (secret ^ (secret << 11)) % PRUNE_MOD
 }
 
-pub fn part1(input: &str) -> Num {
+/// Initial secret numbers of the buyers
+pub fn parse(input: &str) -> Result<Vec<Num>, ParseError> {
     input
         .lines()
-        .map(|line| {
-            let mut secret = line.parse().unwrap();
-            (0..2000).for_each(|_| {
-                secret = next_secret(secret);
-            });
+        .map(|line| parse_number(input, line))
+        .collect()
+}
 
-            secret
-        })
-        .sum()
+pub fn part1(input: &str) -> Result<Num, ParseError> {
+    let secrets = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part1_solve(secrets)))
 }
 
-pub fn part2(input: &str) -> Num {
-    let benefit_per_sequence = input.lines().fold(HashMap::new(), |mut global_acc, line| {
-        let mut secret = line.parse::<Num>().unwrap();
+pub fn part1_solve(secrets: Vec<Num>) -> Num {
+    par_sum(&secrets, |&secret| {
+        (0..2000).fold(secret, |secret, _| next_secret(secret))
+    })
+}
+
+pub fn part2(input: &str) -> Result<Num, ParseError> {
+    let secrets = info_span!("parse").in_scope(|| parse(input))?;
+    Ok(info_span!("solve").in_scope(|| part2_solve(secrets)))
+}
 
-        let secrets = iter::once(secret).chain((0..2000).map(|_| {
-            secret = next_secret(secret);
-            secret
-        }));
+pub fn part2_solve(secrets: Vec<Num>) -> Num {
+    // Each thread adds up its own buyers, the totals are then added up
+    let benefit_per_sequence = par_fold(
+        &secrets,
+        HashMap::new,
+        |mut global_acc, &secret| {
+            let mut secret = secret;
+            let secrets = iter::once(secret).chain((0..2000).map(|_| {
+                secret = next_secret(secret);
+                secret
+            }));
 
-        let prices = secrets.map(|s| s % 10).collect::<Vec<_>>();
+            let prices = secrets.map(|s| s % 10).collect::<Vec<_>>();
 
-        let price_variations = prices
-            .windows(2)
-            .map(|w| (w[1] as i64 - w[0] as i64, w[1]))
-            .collect::<Vec<_>>();
+            let price_variations = prices
+                .windows(2)
+                .map(|w| (w[1] as i64 - w[0] as i64, w[1]))
+                .collect::<Vec<_>>();
 
-        let prices_per_sequence =
-            price_variations
-                .windows(4)
-                .rev()
-                .fold(HashMap::new(), |mut acc, w| {
-                    acc.insert(w.iter().map(|entry| entry.0).collect::<Vec<_>>(), w[3].1);
+            let prices_per_sequence =
+                price_variations
+                    .windows(4)
+                    .rev()
+                    .fold(HashMap::new(), |mut acc, w| {
+                        acc.insert(w.iter().map(|entry| entry.0).collect::<Vec<_>>(), w[3].1);
 
-                    acc
-                });
+                        acc
+                    });
 
-        prices_per_sequence.into_iter().for_each(|(k, v)| {
-            global_acc.entry(k).and_modify(|e| *e += v).or_insert(v);
-        });
+            prices_per_sequence.into_iter().for_each(|(k, v)| {
+                global_acc.entry(k).and_modify(|e| *e += v).or_insert(v);
+            });
 
-        global_acc
-    });
+            global_acc
+        },
+        |mut a, b| {
+            b.into_iter().for_each(|(k, v)| {
+                a.entry(k).and_modify(|e| *e += v).or_insert(v);
+            });
+            a
+        },
+    );
 
-    *benefit_per_sequence.values().max().unwrap()
+    // Without any buyer, no bananas can be sold
+    benefit_per_sequence.values().max().copied().unwrap_or(0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_next_secret() {
@@ -86,25 +111,48 @@ mod tests {
 2024
 "};
 
-    #[test]
-    fn example() {
-        assert_eq!(part1(EXAMPLE), 37327623);
-        assert_eq!(part2(EXAMPLE_2), 23);
+    crate::utils::day_tests! {
+        day: 22,
+        examples: {
+            part1(EXAMPLE) => 37327623,
+            part2(EXAMPLE_2) => 23,
+        },
+        input: {
+            part1 => 12979353889,
+            // part2 => 1449,
+        },
     }
 
-    #[test]
-    fn run_part1() {
-        let input = crate::utils::get_day_input!();
-        let output = part1(&input);
-        println!("Part 1: {}", output);
-        assert_eq!(output, 12979353889);
+    /// Scan the prices of each buyer from the start, keeping the first price of each sequence of
+    /// changes, then add up the buyers
+    fn naive_best_sequence(secrets: &[Num]) -> Num {
+        let mut bananas = HashMap::<[i64; 4], Num>::new();
+        for &secret in secrets {
+            let prices = iter::successors(Some(secret), |&s| Some(next_secret(s)))
+                .take(2001)
+                .map(|s| (s % 10) as i64)
+                .collect::<Vec<_>>();
+
+            let mut first_prices = HashMap::new();
+            for i in 4..prices.len() {
+                let changes = [3, 2, 1, 0].map(|j| prices[i - j] - prices[i - j - 1]);
+                first_prices.entry(changes).or_insert(prices[i] as Num);
+            }
+            for (changes, price) in first_prices {
+                *bananas.entry(changes).or_default() += price;
+            }
+        }
+
+        bananas.into_values().max().unwrap_or(0)
     }
 
-    #[test]
-    fn run_part2() {
-        // let input = crate::utils::get_day_input!();
-        // let output = part2(&input);
-        // println!("Part 2: {}", output);
-        // assert_eq!(output, 1449);
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn naive(secrets in prop::collection::vec(1..PRUNE_MOD, 1..3)) {
+            let input = secrets.iter().map(|s| format!("{s}\n")).collect::<String>();
+            prop_assert_eq!(part2(&input), Ok(naive_best_sequence(&secrets)));
+        }
     }
 }
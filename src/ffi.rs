@@ -0,0 +1,248 @@
+//! C ABI of the solvers, see `include/aoc24.h`
+use std::{
+    ffi::{c_char, CStr},
+    panic::{self, AssertUnwindSafe},
+    slice,
+};
+
+use crate::{solve, SolveError};
+
+/// Status returned by the functions of the C ABI, values are stable
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AocStatus {
+    Ok = 0,
+    /// The day is not part of the calendar
+    InvalidDay = 1,
+    /// Puzzles only have a part 1 and a part 2
+    InvalidPart = 2,
+    /// The day is part of the calendar but has not been solved yet
+    NotImplemented = 3,
+    /// The input of the day is malformed, the location is written to the output buffer
+    ParseError = 4,
+    /// The input is not valid UTF-8
+    InvalidUtf8 = 5,
+    /// A required pointer is null
+    NullPointer = 6,
+    /// The output buffer cannot hold the result, `out_len` is set to the required size
+    BufferTooSmall = 7,
+    /// The solver panicked
+    Panic = 8,
+    /// The input is well-formed but the puzzle has no answer for it
+    NoAnswer = 9,
+}
+
+impl From<&SolveError> for AocStatus {
+    fn from(e: &SolveError) -> Self {
+        match e {
+            SolveError::InvalidDay(_) => AocStatus::InvalidDay,
+            SolveError::InvalidPart(_) => AocStatus::InvalidPart,
+            SolveError::NotImplemented(_) => AocStatus::NotImplemented,
+            SolveError::Parse(_) => AocStatus::ParseError,
+            SolveError::NoAnswer => AocStatus::NoAnswer,
+        }
+    }
+}
+
+/// Copy `s` to the output buffer as a nul-terminated string, `out_len` is set to the length of `s`
+unsafe fn write_out(s: &str, out_buf: *mut c_char, out_len: *mut usize) -> bool {
+    let capacity = *out_len;
+    *out_len = s.len();
+    if out_buf.is_null() || capacity <= s.len() {
+        return false;
+    }
+
+    let out = slice::from_raw_parts_mut(out_buf as *mut u8, capacity);
+    out[..s.len()].copy_from_slice(s.as_bytes());
+    out[s.len()] = 0;
+    true
+}
+
+/// Solve `part` of `day` with the `len` bytes at `input`, which must be UTF-8.
+///
+/// `*out_len` is the capacity of `out_buf`. The answer, or the error message on failure, is
+/// written to `out_buf` as a nul-terminated string and `*out_len` is set to its length, without
+/// the nul terminator. When the buffer is too small, nothing is written, `*out_len` is still set
+/// and the `BufferTooSmall` status is returned, unless solving failed in the first place.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, `out_len` must be valid for reads and writes and
+/// `out_buf` must point to `*out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    len: usize,
+    out_buf: *mut c_char,
+    out_len: *mut usize,
+) -> AocStatus {
+    if out_len.is_null() || (input.is_null() && len > 0) {
+        return AocStatus::NullPointer;
+    }
+
+    let input = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, len)
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        write_out("input is not valid UTF-8", out_buf, out_len);
+        return AocStatus::InvalidUtf8;
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solve(day, part, input)));
+    let (message, status) = match result {
+        Ok(Ok(answer)) => (answer.to_string(), AocStatus::Ok),
+        Ok(Err(e)) => (e.to_string(), AocStatus::from(&e)),
+        Err(_) => ("solver panicked".to_string(), AocStatus::Panic),
+    };
+
+    match (write_out(&message, out_buf, out_len), status) {
+        (false, AocStatus::Ok) => AocStatus::BufferTooSmall,
+        (_, status) => status,
+    }
+}
+
+/// Static nul-terminated description of a status
+#[no_mangle]
+pub extern "C" fn aoc_status_str(status: AocStatus) -> *const c_char {
+    let description: &CStr = match status {
+        AocStatus::Ok => c"ok",
+        AocStatus::InvalidDay => c"invalid day",
+        AocStatus::InvalidPart => c"invalid part",
+        AocStatus::NotImplemented => c"day not implemented",
+        AocStatus::ParseError => c"malformed input",
+        AocStatus::InvalidUtf8 => c"input is not valid UTF-8",
+        AocStatus::NullPointer => c"null pointer",
+        AocStatus::BufferTooSmall => c"output buffer too small",
+        AocStatus::Panic => c"solver panicked",
+        AocStatus::NoAnswer => c"no answer",
+    };
+    description.as_ptr()
+}
+
+/// Whether `day` is implemented and can be passed to `aoc_solve`
+#[no_mangle]
+pub extern "C" fn aoc_is_implemented(day: u8) -> bool {
+    crate::puzzle(day).is_some_and(|puzzle| puzzle.implemented)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY01: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    fn call(day: u8, part: u8, input: &[u8], capacity: usize) -> (AocStatus, usize, String) {
+        let mut out = vec![0xff_u8; capacity];
+        let mut out_len = capacity;
+        let status = unsafe {
+            aoc_solve(
+                day,
+                part,
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr() as *mut c_char,
+                &mut out_len,
+            )
+        };
+        let written = CStr::from_bytes_until_nul(&out)
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        (status, out_len, written)
+    }
+
+    #[test]
+    fn solve() {
+        assert_eq!(
+            call(1, 1, DAY01.as_bytes(), 64),
+            (AocStatus::Ok, 2, "11".to_string())
+        );
+        assert_eq!(
+            call(14, 1, b"p=0,4 v=3,-3\np=6,3 w=-1,-3\n", 64),
+            (
+                AocStatus::ParseError,
+                27,
+                "line 2 col 7: expected `v=`".to_string()
+            )
+        );
+        assert_eq!(
+            call(16, 1, b"#####\n#S#E#\n#####\n", 64),
+            (
+                AocStatus::NoAnswer,
+                23,
+                "the input has no answer".to_string()
+            )
+        );
+        assert_eq!(call(26, 1, b"", 64).0, AocStatus::InvalidDay);
+        assert_eq!(call(1, 3, b"", 64).0, AocStatus::InvalidPart);
+        assert_eq!(call(25, 1, b"", 64).0, AocStatus::NotImplemented);
+        assert_eq!(call(1, 1, b"\xff", 64).0, AocStatus::InvalidUtf8);
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        // The nul terminator needs room too
+        assert_eq!(
+            call(1, 1, DAY01.as_bytes(), 2),
+            (AocStatus::BufferTooSmall, 2, String::new())
+        );
+        assert_eq!(
+            call(1, 1, DAY01.as_bytes(), 3),
+            (AocStatus::Ok, 2, "11".to_string())
+        );
+
+        let mut out_len = 0;
+        let status = unsafe {
+            aoc_solve(
+                1,
+                1,
+                DAY01.as_ptr(),
+                DAY01.len(),
+                std::ptr::null_mut(),
+                &mut out_len,
+            )
+        };
+        assert_eq!((status, out_len), (AocStatus::BufferTooSmall, 2));
+    }
+
+    #[test]
+    fn null_pointers() {
+        let status = unsafe {
+            aoc_solve(
+                1,
+                1,
+                DAY01.as_ptr(),
+                DAY01.len(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, AocStatus::NullPointer);
+
+        let mut out_len = 0;
+        let status = unsafe {
+            aoc_solve(
+                1,
+                1,
+                std::ptr::null(),
+                1,
+                std::ptr::null_mut(),
+                &mut out_len,
+            )
+        };
+        assert_eq!(status, AocStatus::NullPointer);
+    }
+
+    #[test]
+    fn metadata() {
+        assert!(aoc_is_implemented(1));
+        assert!(!aoc_is_implemented(25));
+        assert!(!aoc_is_implemented(0));
+
+        let description = unsafe { CStr::from_ptr(aoc_status_str(AocStatus::BufferTooSmall)) };
+        assert_eq!(description.to_str(), Ok("output buffer too small"));
+    }
+}
@@ -0,0 +1,600 @@
+//! Seeded random inputs for every day, to stress test and benchmark the solvers without sharing
+//! real inputs. The same day, scale and seed always generate the same input.
+use rand::{
+    seq::{IndexedRandom, SliceRandom},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    puzzle,
+    utils::{AdjacentPositions, Direction, DirectionSet},
+    SolveError,
+};
+
+type Generator = fn(&mut ChaCha8Rng, usize) -> String;
+type Grid = Vec<Vec<u8>>;
+
+/// Generator of each implemented day, with the scale of the real inputs
+const GENERATORS: &[(Generator, usize)] = &[
+    (day01, 1000),
+    (day02, 1000),
+    (day03, 700),
+    (day04, 140),
+    (day05, 200),
+    (day06, 130),
+    (day07, 850),
+    (day08, 50),
+    (day09, 19999),
+    (day10, 55),
+    (day11, 8),
+    (day12, 140),
+    (day13, 320),
+    (day14, 500),
+    (day15, 50),
+    (day16, 141),
+    (day17, 9),
+    (day18, 71),
+    (day19, 400),
+    (day20, 141),
+    (day21, 5),
+    (day22, 2000),
+];
+
+fn generator(day: u8) -> Result<(Generator, usize), SolveError> {
+    puzzle(day).ok_or(SolveError::InvalidDay(day))?;
+    GENERATORS
+        .get(day as usize - 1)
+        .copied()
+        .ok_or(SolveError::NotImplemented(day))
+}
+
+/// Scale of the real inputs of a day, see [`generate`] for its meaning
+pub fn default_scale(day: u8) -> Result<usize, SolveError> {
+    generator(day).map(|(_, scale)| scale)
+}
+
+/// Valid random input for a day. The meaning of `scale` depends on the day:
+///
+/// - lines of numbers for days 01, 02, 07 and 22, instructions for day 03
+/// - side of the grid for days 04, 06, 08, 10, 12, 15, 16, 18 and 20
+/// - updates for day 05, machines for day 13, robots for day 14, patterns for day 19 and codes
+///   for day 21
+/// - length of the disk map for day 09, stones for day 11 and octal digits of register A for
+///   day 17
+///
+/// Scales too small for a valid input are raised to the smallest valid one.
+/// ```
+/// use aoc24::{generate::generate, solve};
+///
+/// let input = generate(1, 10, 42).unwrap();
+/// assert_eq!(input.lines().count(), 10);
+/// assert_eq!(input, generate(1, 10, 42).unwrap());
+/// assert!(solve(1, 1, &input).is_ok());
+/// ```
+pub fn generate(day: u8, scale: usize, seed: u64) -> Result<String, SolveError> {
+    let (generator, _) = generator(day)?;
+    Ok(generator(&mut ChaCha8Rng::seed_from_u64(seed), scale))
+}
+
+fn grid_to_string(grid: &[Vec<u8>]) -> String {
+    grid.iter()
+        .flat_map(|row| row.iter().copied().chain([b'\n']))
+        .map(char::from)
+        .collect()
+}
+
+/// Maze of `#` with `.` corridors on odd coordinates, carved by a depth-first search from
+/// `start`. Returns the parent of each cell of the corridors, two cells away.
+fn carve_maze(
+    rng: &mut ChaCha8Rng,
+    side: usize,
+    start: (usize, usize),
+) -> (Grid, Vec<Vec<(usize, usize)>>) {
+    let mut grid = vec![vec![b'#'; side]; side];
+    let mut parents = vec![vec![start; side]; side];
+    let mut stack = vec![start];
+    grid[start.0][start.1] = b'.';
+
+    while let Some(&(i, j)) = stack.last() {
+        let nexts = [(0, 2), (2, 0), (0, -2), (-2, 0)]
+            .into_iter()
+            .map(|(di, dj)| ((i as isize + di) as usize, (j as isize + dj) as usize))
+            .filter(|&(ni, nj)| {
+                (1..side - 1).contains(&ni) && (1..side - 1).contains(&nj) && grid[ni][nj] == b'#'
+            })
+            .collect::<Vec<_>>();
+
+        let Some(&(ni, nj)) = nexts.choose(rng) else {
+            stack.pop();
+            continue;
+        };
+        grid[(i + ni) / 2][(j + nj) / 2] = b'.';
+        grid[ni][nj] = b'.';
+        parents[ni][nj] = (i, j);
+        stack.push((ni, nj));
+    }
+
+    (grid, parents)
+}
+
+fn day01(rng: &mut ChaCha8Rng, lines: usize) -> String {
+    let left = (0..lines)
+        .map(|_| rng.random_range(10000..100000))
+        .collect::<Vec<u32>>();
+
+    left.iter()
+        .map(|l| {
+            // Some numbers of the right list appear in the left one, for part 2
+            let r = match rng.random_bool(0.3) {
+                true => *left.choose(rng).unwrap(),
+                false => rng.random_range(10000..100000),
+            };
+            format!("{l}   {r}\n")
+        })
+        .collect()
+}
+
+fn day02(rng: &mut ChaCha8Rng, lines: usize) -> String {
+    (0..lines)
+        .map(|_| {
+            let sign = if rng.random_bool(0.5) { 1 } else { -1 };
+            let mut level: i32 = rng.random_range(40..60);
+            let mut report = vec![level];
+            for _ in 1..rng.random_range(5..=8) {
+                // A few steps make the report unsafe
+                let step = match rng.random_bool(0.05) {
+                    true => rng.random_range(-3..=5),
+                    false => rng.random_range(1..=3),
+                };
+                level += sign * step;
+                report.push(level);
+            }
+
+            let report = report.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+            report.join(" ") + "\n"
+        })
+        .collect()
+}
+
+fn day03(rng: &mut ChaCha8Rng, instructions: usize) -> String {
+    const NOISE: &[&str] = &[
+        "!", "@", "#", "$", "%", "^", "&", "*", "(", ")", "[", "]", "{", "}", "<", ">", ",", ";",
+        ":", "'", "?", " ", "+", "-", "_", "~", "what", "from", "select", "who", "where", "how",
+    ];
+
+    let mut memory = String::new();
+    for _ in 0..instructions {
+        for _ in 0..rng.random_range(0..4) {
+            memory.push_str(NOISE.choose(rng).unwrap());
+        }
+
+        let (a, b) = (rng.random_range(1..1000), rng.random_range(1..1000));
+        let instruction = match rng.random_range(0..10) {
+            0 => "do()".to_string(),
+            1 => "don't()".to_string(),
+            2 => format!("mul({a}*{b})"),
+            3 => format!("mul[{a},{b}]"),
+            4 => format!("mul ( {a},{b})"),
+            _ => format!("mul({a},{b})"),
+        };
+        memory.push_str(&instruction);
+
+        if rng.random_range(0..instructions.div_ceil(6)) == 0 {
+            memory.push('\n');
+        }
+    }
+
+    memory + "\n"
+}
+
+fn day04(rng: &mut ChaCha8Rng, side: usize) -> String {
+    let side = side.max(1);
+    let grid = (0..side)
+        .map(|_| (0..side).map(|_| *b"XMAS".choose(rng).unwrap()).collect())
+        .collect::<Vec<_>>();
+
+    grid_to_string(&grid)
+}
+
+fn day05(rng: &mut ChaCha8Rng, updates: usize) -> String {
+    const PAGES: usize = 49;
+
+    // Pages are ranked by their index, every pair of pages has a rule
+    let mut pages = (10..100).collect::<Vec<u32>>();
+    pages.shuffle(rng);
+    pages.truncate(PAGES);
+
+    let mut rules = (0..PAGES)
+        .flat_map(|i| (i + 1..PAGES).map(move |j| (i, j)))
+        .map(|(i, j)| format!("{}|{}\n", pages[i], pages[j]))
+        .collect::<Vec<_>>();
+    rules.shuffle(rng);
+
+    let updates = (0..updates).map(|_| {
+        let mut update = (0..PAGES).collect::<Vec<_>>();
+        update.shuffle(rng);
+        // Updates have a middle page
+        update.truncate(rng.random_range(2..=11) * 2 + 1);
+        if rng.random_bool(0.5) {
+            update.sort_unstable();
+        }
+
+        let update = update
+            .iter()
+            .map(|&i| pages[i].to_string())
+            .collect::<Vec<_>>();
+        update.join(",") + "\n"
+    });
+
+    rules.concat() + "\n" + &updates.collect::<String>()
+}
+
+fn day06(rng: &mut ChaCha8Rng, side: usize) -> String {
+    let side = side.max(1);
+
+    // Whether the guard leaves the map instead of walking in a loop
+    let escapes = |grid: &[Vec<u8>], (mut i, mut j): (usize, usize)| {
+        let mut direction = Direction::North;
+        let mut visited = vec![DirectionSet::default(); side * side];
+        loop {
+            if !visited[i * side + j].insert(direction) {
+                return false;
+            }
+
+            let Some((ni, nj)) = (i, j).step(direction, (side, side)) else {
+                return true;
+            };
+
+            match grid[ni][nj] {
+                b'#' => direction = direction.turn_right(),
+                _ => (i, j) = (ni, nj),
+            }
+        }
+    };
+
+    loop {
+        let mut grid = (0..side)
+            .map(|_| {
+                (0..side)
+                    .map(|_| if rng.random_bool(0.05) { b'#' } else { b'.' })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let guard = (rng.random_range(0..side), rng.random_range(0..side));
+        grid[guard.0][guard.1] = b'^';
+
+        if escapes(&grid, guard) {
+            return grid_to_string(&grid);
+        }
+    }
+}
+
+fn day07(rng: &mut ChaCha8Rng, lines: usize) -> String {
+    // Keeps the intermediate values of the solvers within `u64`
+    const MAX_TEST_VALUE: u64 = 10_u64.pow(13);
+
+    (0..lines)
+        .map(|_| loop {
+            let numbers = (0..rng.random_range(3..=12))
+                .map(|_| match rng.random_bool(0.8) {
+                    true => rng.random_range(1..10),
+                    false => rng.random_range(10..1000),
+                })
+                .collect::<Vec<u64>>();
+
+            let test_value = numbers[1..].iter().try_fold(numbers[0], |value, &n| {
+                match rng.random_range(0..3) {
+                    0 => value.checked_add(n),
+                    1 => value.checked_mul(n),
+                    _ => format!("{value}{n}").parse().ok(),
+                }
+                .filter(|&value| value <= MAX_TEST_VALUE)
+            });
+
+            if let Some(test_value) = test_value {
+                // About half of the equations cannot be solved
+                let test_value = test_value + rng.random_range(0..2);
+                let numbers = numbers.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+                break format!("{test_value}: {}\n", numbers.join(" "));
+            }
+        })
+        .collect()
+}
+
+fn day08(rng: &mut ChaCha8Rng, side: usize) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    let side = side.max(1);
+    let frequencies = &FREQUENCIES[..side.min(FREQUENCIES.len())];
+    let mut grid = vec![vec![b'.'; side]; side];
+    for _ in 0..side * side / 16 {
+        let (i, j) = (rng.random_range(0..side), rng.random_range(0..side));
+        grid[i][j] = *frequencies.choose(rng).unwrap();
+    }
+
+    grid_to_string(&grid)
+}
+
+fn day09(rng: &mut ChaCha8Rng, length: usize) -> String {
+    // Starts and ends with a file, files are never empty
+    (0..(length | 1))
+        .map(|i| match i % 2 {
+            0 => rng.random_range(1..=9),
+            _ => rng.random_range(0..=9),
+        })
+        .map(|digit: u8| char::from(b'0' + digit))
+        .chain(['\n'])
+        .collect()
+}
+
+fn day10(rng: &mut ChaCha8Rng, side: usize) -> String {
+    // Diagonal slopes make hiking trails, with a bit of noise to branch them
+    let side = side.max(1);
+    let grid = (0..side)
+        .map(|i| {
+            (0..side)
+                .map(|j| b'0' + ((i + j + rng.random_bool(0.1) as usize) % 10) as u8)
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    grid_to_string(&grid)
+}
+
+fn day11(rng: &mut ChaCha8Rng, stones: usize) -> String {
+    let stones = (0..stones.max(1))
+        .map(|_| rng.random_range(0..10_000_000).to_string())
+        .collect::<Vec<_>>();
+
+    stones.join(" ") + "\n"
+}
+
+fn day12(rng: &mut ChaCha8Rng, side: usize) -> String {
+    // Checkerboard of 10x10 areas with mostly one plant, the others make small regions
+    let side = side.max(1);
+    let grid = (0..side)
+        .map(|i| {
+            (0..side)
+                .map(|j| {
+                    let plants = if (i / 10 + j / 10) % 2 == 0 {
+                        b"AAAAB"
+                    } else {
+                        b"CCCDE"
+                    };
+                    *plants.choose(rng).unwrap()
+                })
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    grid_to_string(&grid)
+}
+
+fn day13(rng: &mut ChaCha8Rng, machines: usize) -> String {
+    let machines = (0..machines.max(1))
+        .map(|_| {
+            let [ax, ay, bx, by] = [(); 4].map(|_| rng.random_range(10..100));
+            let (a, b) = (rng.random_range(0..=100), rng.random_range(0..=100));
+            // Some prizes cannot be won
+            let px = a * ax + b * bx + rng.random_range(0..3) / 2;
+            let py = a * ay + b * by;
+
+            format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={px}, Y={py}\n")
+        })
+        .collect::<Vec<_>>();
+
+    machines.join("\n")
+}
+
+fn day14(rng: &mut ChaCha8Rng, robots: usize) -> String {
+    const WIDTH: i64 = 101;
+    const HEIGHT: i64 = 103;
+
+    // Robots draw the frame of the easter egg at some point, which part 2 detects with 35
+    // robots in a column and 25 in a row
+    let time = rng.random_range(1..WIDTH * HEIGHT);
+    let (x, y) = (
+        rng.random_range(0..WIDTH - 25),
+        rng.random_range(0..HEIGHT - 35),
+    );
+    let frame = (0..35)
+        .map(|i| (x, y + i))
+        .chain((0..25).map(|i| (x + i, y)));
+
+    let random =
+        std::iter::repeat_with(|| (rng.random_range(0..WIDTH), rng.random_range(0..HEIGHT)))
+            .take(robots.saturating_sub(60))
+            .collect::<Vec<_>>();
+
+    frame
+        .chain(random)
+        .map(|(x, y)| {
+            let (vx, vy) = (rng.random_range(-99..=99), rng.random_range(-99..=99));
+            let px = (x - vx * time).rem_euclid(WIDTH);
+            let py = (y - vy * time).rem_euclid(HEIGHT);
+            format!("p={px},{py} v={vx},{vy}\n")
+        })
+        .collect()
+}
+
+fn day15(rng: &mut ChaCha8Rng, side: usize) -> String {
+    let side = side.max(3);
+    let mut grid = (0..side)
+        .map(|i| {
+            (0..side)
+                .map(|j| match rng.random_range(0..100) {
+                    _ if i == 0 || j == 0 || i == side - 1 || j == side - 1 => b'#',
+                    0..5 => b'#',
+                    5..15 => b'O',
+                    _ => b'.',
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<_>>>();
+    grid[rng.random_range(1..side - 1)][rng.random_range(1..side - 1)] = b'@';
+
+    let moves = (0..side * side * 8)
+        .map(|_| *b"<>^v".choose(rng).unwrap())
+        .collect::<Vec<_>>();
+    let moves = moves.chunks(1000).collect::<Vec<_>>();
+
+    grid_to_string(&grid)
+        + "\n"
+        + &grid_to_string(&moves.iter().map(|m| m.to_vec()).collect::<Vec<_>>())
+}
+
+fn day16(rng: &mut ChaCha8Rng, side: usize) -> String {
+    let side = side.max(5) | 1;
+    let (mut grid, _) = carve_maze(rng, side, (side - 2, 1));
+
+    // Openings in the walls make several best paths
+    for _ in 0..side * 2 {
+        grid[rng.random_range(1..side - 1)][rng.random_range(1..side - 1)] = b'.';
+    }
+    grid[side - 2][1] = b'S';
+    grid[1][side - 2] = b'E';
+
+    grid_to_string(&grid)
+}
+
+fn day17(rng: &mut ChaCha8Rng, digits: usize) -> String {
+    // Programs output a function of the lowest 3 bits of A, and shift A until it is 0
+    let digits = digits.clamp(1, 21) as u32;
+    loop {
+        let a = rng.random_range(8_u64.pow(digits - 1)..8_u64.pow(digits));
+        let (x, y) = (rng.random_range(0..8), rng.random_range(0..8));
+        let input = format!(
+            "Register A: {a}\nRegister B: 0\nRegister C: 0\n\n\
+             Program: 2,4,1,{x},7,5,1,{y},4,{x},5,5,0,3,3,0\n"
+        );
+
+        if crate::day17::has_quine(&input) {
+            return input;
+        }
+    }
+}
+
+fn day18(rng: &mut ChaCha8Rng, side: usize) -> String {
+    // Part 1 drops 1024 bytes, which must leave a path
+    let side = side.max(33);
+
+    // Bytes only fall on a random path from the start to the exit once all other cells are
+    // corrupted, so that part 2 also has an answer
+    let (mut x, mut y) = (0, 0);
+    let mut path = vec![vec![false; side]; side];
+    while (x, y) != (side - 1, side - 1) {
+        path[y][x] = true;
+        match (x < side - 1, y < side - 1) {
+            (true, true) if rng.random_bool(0.5) => x += 1,
+            (true, true) => y += 1,
+            (true, false) => x += 1,
+            _ => y += 1,
+        }
+    }
+    path[y][x] = true;
+
+    let (mut on_path, mut off_path): (Vec<_>, Vec<_>) = (0..side)
+        .flat_map(|y| (0..side).map(move |x| (x, y)))
+        .filter(|&position| position != (0, 0) && position != (side - 1, side - 1))
+        .partition(|&(x, y)| path[y][x]);
+    off_path.shuffle(rng);
+    on_path.shuffle(rng);
+
+    off_path
+        .into_iter()
+        .chain(on_path)
+        .map(|(x, y)| format!("{x},{y}\n"))
+        .collect()
+}
+
+fn day19(rng: &mut ChaCha8Rng, patterns: usize) -> String {
+    // Short towels never end with `w`, which makes some patterns impossible
+    let mut towels = (0..450)
+        .map(|_| {
+            (0..rng.random_range(1..=8))
+                .map(|_| *b"wubrg".choose(rng).unwrap() as char)
+                .collect::<String>()
+        })
+        .filter(|towel| towel.len() > 2 || !towel.ends_with('w'))
+        .collect::<Vec<_>>();
+    towels.sort_unstable();
+    towels.dedup();
+
+    let patterns = (0..patterns)
+        .map(|_| {
+            (0..rng.random_range(20..=60))
+                .map(|_| *b"wubrg".choose(rng).unwrap() as char)
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect::<String>();
+
+    towels.join(", ") + "\n\n" + &patterns
+}
+
+fn day20(rng: &mut ChaCha8Rng, side: usize) -> String {
+    // The racetrack is the only path of a maze between its corners
+    let side = side.max(5) | 1;
+    let (start, end) = ((side - 2, 1), (1, side - 2));
+    let (_, parents) = carve_maze(rng, side, start);
+
+    let mut grid = vec![vec![b'#'; side]; side];
+    let mut position = end;
+    while position != start {
+        let parent = parents[position.0][position.1];
+        grid[position.0][position.1] = b'.';
+        grid[(position.0 + parent.0) / 2][(position.1 + parent.1) / 2] = b'.';
+        position = parent;
+    }
+    grid[start.0][start.1] = b'S';
+    grid[end.0][end.1] = b'E';
+
+    grid_to_string(&grid)
+}
+
+fn day21(rng: &mut ChaCha8Rng, codes: usize) -> String {
+    (0..codes)
+        .map(|_| format!("{:03}A\n", rng.random_range(0..1000)))
+        .collect()
+}
+
+fn day22(rng: &mut ChaCha8Rng, buyers: usize) -> String {
+    (0..buyers)
+        .map(|_| format!("{}\n", rng.random_range(1..1 << 24)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{implemented_days, solve};
+
+    #[test]
+    fn deterministic() {
+        for day in implemented_days() {
+            assert_eq!(generate(day, 10, 1), generate(day, 10, 1));
+        }
+        assert_ne!(generate(9, 100, 1), generate(9, 100, 2));
+    }
+
+    #[test]
+    fn solvable() {
+        for day in implemented_days() {
+            for seed in 0..3 {
+                let input = generate(day, 10, seed).unwrap();
+                for part in 1..=2 {
+                    let result = solve(day, part, &input);
+                    assert!(result.is_ok(), "day {day} part {part}: {result:?}\n{input}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(generate(0, 10, 0), Err(SolveError::InvalidDay(0)));
+        assert_eq!(generate(25, 10, 0), Err(SolveError::NotImplemented(25)));
+        assert_eq!(default_scale(18), Ok(71));
+    }
+}
@@ -1,5 +1,16 @@
 pub mod utils;
 
+pub use solve::*;
+
+mod solve;
+
+pub mod ffi;
+#[cfg(feature = "generate")]
+pub mod generate;
+
+#[cfg(feature = "python")]
+mod python;
+
 // #[allow(dead_code, unused_variables)]
 // mod day_template;
 
@@ -1,32 +1,81 @@
 use aoc24::*;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use paste::paste;
-use std::time::Instant;
+use std::{path::PathBuf, time::Instant};
+use tracing::info_span;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{
+    filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
 
-const N_DAYS: u8 = 25;
+mod dashboard;
 
 #[derive(Parser, Debug)]
 pub struct Args {
     /// Day to run, if not specified all days will be run
     #[arg(short, long)]
     pub day: Option<u8>,
+
+    /// Export the parse and solve spans to this file, in the Chrome trace event format
+    #[arg(long, global = true)]
+    pub trace: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Interactive grid of the calendar, to run days and compare their answers and timings
+    Dashboard,
+    /// Random input of a day, printed to stdout, or of every day with `--output`
+    Generate {
+        /// Day to generate, every implemented day if not specified
+        #[arg(short, long)]
+        day: Option<u8>,
+
+        /// Size of the input, its meaning depends on the day. Defaults to the size of the real inputs
+        #[arg(short, long)]
+        scale: Option<usize>,
+
+        /// Inputs with the same day, scale and seed are identical
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Directory to write `dayXX.txt` files to
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 macro_rules! run_day {
     ($day:expr) => {{
         paste! {
-            let input = include_str!(concat!("../inputs/day", stringify!($day), ".txt"));
+            let path = concat!("./inputs/day", stringify!($day), ".txt");
+            let Ok(input) = std::fs::read_to_string(path) else {
+                eprintln!("Skipping day {}: could not read {}", $day, path);
+                return;
+            };
+            let input = input.as_str();
             println!("Running day {}", $day);
+            let _day_span = info_span!("day", day = $day).entered();
 
             let start = Instant::now();
-            let part1_result = [<day $day>]::part1(input);
+            let part1_result = info_span!("part", part = 1).in_scope(|| [<day $day>]::part1(input));
             let elapsed1 = start.elapsed();
-            println!("Part1:\n{} ({:.2?})", part1_result, elapsed1);
+            match part1_result {
+                Ok(result) => println!("Part1:\n{} ({:.2?})", result, elapsed1),
+                Err(e) => eprintln!("day{}: {}", stringify!($day), e),
+            }
 
             let start = Instant::now();
-            let part2_result = [<day $day>]::part2(input);
+            let part2_result = info_span!("part", part = 2).in_scope(|| [<day $day>]::part2(input));
             let elapsed2 = start.elapsed();
-            println!("Part2:\n{} ({:.2?})", part2_result, elapsed2);
+            match part2_result {
+                Ok(result) => println!("Part2:\n{} ({:.2?})", result, elapsed2),
+                Err(e) => eprintln!("day{}: {}", stringify!($day), e),
+            }
 
             println!();
         }
@@ -70,6 +119,37 @@ fn run_day(day: u8) {
     }
 }
 
+fn generate_inputs(
+    day: Option<u8>,
+    scale: Option<usize>,
+    seed: u64,
+    output: Option<&PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let days = match (day, output) {
+        (Some(day), _) => vec![day],
+        (None, Some(_)) => implemented_days().collect(),
+        (None, None) => return Err("--output is required to generate every day".into()),
+    };
+
+    for day in days {
+        let scale = match scale {
+            Some(scale) => scale,
+            None => generate::default_scale(day)?,
+        };
+        let input = generate::generate(day, scale, seed)?;
+
+        match output {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                std::fs::write(dir.join(format!("day{:02}.txt", day)), input)?;
+            }
+            None => print!("{}", input),
+        }
+    }
+
+    Ok(())
+}
+
 fn run_all_days() {
     println!("Running all days\n");
 
@@ -81,9 +161,60 @@ fn run_all_days() {
     println!("Ran all days in {:.2?}", elapsed);
 }
 
+/// Spans are logged to stderr when they close, according to `RUST_LOG`. With `--trace`, they are
+/// also recorded down to the debug level, which includes the inner loops of some days.
+fn init_tracing(trace: Option<&PathBuf>, log_to_stderr: bool) -> Option<FlushGuard> {
+    let fmt_layer = log_to_stderr.then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_filter(EnvFilter::from_default_env())
+    });
+
+    let (chrome_layer, guard) = match trace {
+        Some(path) => {
+            let (layer, guard) = ChromeLayerBuilder::new().file(path).build();
+            (Some(layer.with_filter(LevelFilter::DEBUG)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(chrome_layer)
+        .init();
+
+    guard
+}
+
 fn main() {
     let args = Args::parse();
 
+    // The dashboard owns the terminal, logging to stderr would garble it
+    let is_dashboard = matches!(args.command, Some(Command::Dashboard));
+    let _trace_guard = init_tracing(args.trace.as_ref(), !is_dashboard);
+
+    match &args.command {
+        Some(Command::Dashboard) => {
+            if let Err(e) = dashboard::run() {
+                eprintln!("Dashboard error: {}", e);
+            }
+            return;
+        }
+        Some(Command::Generate {
+            day,
+            scale,
+            seed,
+            output,
+        }) => {
+            if let Err(e) = generate_inputs(*day, *scale, *seed, output.as_ref()) {
+                eprintln!("Generate error: {}", e);
+            }
+            return;
+        }
+        None => {}
+    }
+
     match args.day {
         Some(day) => run_day(day),
         None => run_all_days(),
@@ -0,0 +1,172 @@
+//! Python extension module, `import aoc24` once built with `maturin develop --features python`
+use paste::paste;
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyDict};
+
+use crate::{
+    utils::{self, ManhattanDistance},
+    Answer, SolveError,
+};
+
+create_exception!(aoc24, ParseError, PyValueError, "Malformed puzzle input");
+
+impl From<utils::ParseError> for PyErr {
+    fn from(e: utils::ParseError) -> Self {
+        ParseError::new_err(e.to_string())
+    }
+}
+
+impl From<SolveError> for PyErr {
+    fn from(e: SolveError) -> Self {
+        match e {
+            SolveError::Parse(e) => e.into(),
+            e => PyValueError::new_err(e.to_string()),
+        }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for Answer {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        match self {
+            Answer::Number(n) => Ok(n.into_pyobject(py)?.into_any()),
+            Answer::Text(s) => Ok(s.into_pyobject(py)?.into_any()),
+        }
+    }
+}
+
+/// Solve a part of a day, `part` being either 1 or 2
+#[pyfunction]
+fn solve(py: Python<'_>, day: u8, part: u8, input: &str) -> PyResult<Answer> {
+    Ok(py.allow_threads(|| crate::solve(day, part, input))?)
+}
+
+/// Days that can be passed to `solve`
+#[pyfunction]
+fn implemented_days() -> Vec<u32> {
+    // Vec<u8> would be converted to `bytes`
+    crate::implemented_days().map(u32::from).collect()
+}
+
+/// Metadata of every day of the calendar, as dicts of `day`, `title`, `url` and `implemented`
+#[pyfunction]
+fn puzzles(py: Python<'_>) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    crate::puzzles()
+        .map(|puzzle| {
+            let dict = PyDict::new(py);
+            dict.set_item("day", puzzle.day)?;
+            dict.set_item("title", puzzle.title)?;
+            dict.set_item("url", puzzle.url())?;
+            dict.set_item("implemented", puzzle.implemented)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// Submodules are also registered in `sys.modules`, for `from aoc24.day01 import part1` to work
+fn add_submodule(parent: &Bound<'_, PyModule>, module: &Bound<'_, PyModule>) -> PyResult<()> {
+    parent.add_submodule(module)?;
+    parent
+        .py()
+        .import("sys")?
+        .getattr("modules")?
+        .set_item(format!("aoc24.{}", module.name()?), module)
+}
+
+/// Expose the parts of the days as `aoc24.dayNN.part1` and `aoc24.dayNN.part2`
+macro_rules! day_modules {
+    ($($day:literal),*) => {
+        paste! {
+            $(
+                mod [<day $day>] {
+                    use super::*;
+
+                    #[pyfunction]
+                    fn part1(py: Python<'_>, input: &str) -> PyResult<Answer> {
+                        Ok(py.allow_threads(|| crate::[<day $day>]::part1(input).map(Answer::from))?)
+                    }
+
+                    #[pyfunction]
+                    fn part2(py: Python<'_>, input: &str) -> PyResult<Answer> {
+                        Ok(py.allow_threads(|| crate::[<day $day>]::part2(input).map(Answer::from))?)
+                    }
+
+                    pub fn module(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+                        let m = PyModule::new(py, stringify!([<day $day>]))?;
+                        m.add_function(wrap_pyfunction!(part1, &m)?)?;
+                        m.add_function(wrap_pyfunction!(part2, &m)?)?;
+                        Ok(m)
+                    }
+                }
+            )*
+
+            fn add_day_modules(m: &Bound<'_, PyModule>) -> PyResult<()> {
+                $(add_submodule(m, &[<day $day>]::module(m.py())?)?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+day_modules!(
+    01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22
+);
+
+mod py_utils {
+    use super::*;
+
+    /// Coordinates at a manhattan distance of `distance` from `point`, within
+    /// `min_bound..max_bound` on both axes
+    #[pyfunction]
+    fn at_manhattan_distance(
+        point: (usize, usize),
+        distance: usize,
+        min_bound: usize,
+        max_bound: usize,
+    ) -> Vec<(usize, usize)> {
+        point
+            .at_manhattan_distance(distance, (min_bound..max_bound, min_bound..max_bound))
+            .collect()
+    }
+
+    /// Coordinates at a manhattan distance between 0 and `distance` from `point`, within
+    /// `min_bound..max_bound` on both axes
+    #[pyfunction]
+    fn within_manhattan_distance(
+        point: (usize, usize),
+        distance: usize,
+        min_bound: usize,
+        max_bound: usize,
+    ) -> Vec<(usize, usize)> {
+        point
+            .within_manhattan_distance(distance, (min_bound..max_bound, min_bound..max_bound))
+            .collect()
+    }
+
+    /// Number of decimal digits of `n`
+    #[pyfunction]
+    fn number_of_digits(n: u64) -> u32 {
+        utils::number_of_digits_u64(n)
+    }
+
+    pub fn module(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+        let m = PyModule::new(py, "utils")?;
+        m.add_function(wrap_pyfunction!(at_manhattan_distance, &m)?)?;
+        m.add_function(wrap_pyfunction!(within_manhattan_distance, &m)?)?;
+        m.add_function(wrap_pyfunction!(number_of_digits, &m)?)?;
+        Ok(m)
+    }
+}
+
+#[pymodule]
+fn aoc24(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("ParseError", m.py().get_type::<ParseError>())?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_function(wrap_pyfunction!(implemented_days, m)?)?;
+    m.add_function(wrap_pyfunction!(puzzles, m)?)?;
+    add_day_modules(m)?;
+    add_submodule(m, &py_utils::module(m.py())?)?;
+    Ok(())
+}
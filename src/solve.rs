@@ -0,0 +1,324 @@
+use paste::paste;
+use std::fmt;
+
+use crate::{utils::ParseError, *};
+
+/// Number of days in the calendar
+pub const N_DAYS: u8 = 25;
+
+const TITLES: [&str; N_DAYS as usize] = [
+    "Historian Hysteria",
+    "Red-Nosed Reports",
+    "Mull It Over",
+    "Ceres Search",
+    "Print Queue",
+    "Guard Gallivant",
+    "Bridge Repair",
+    "Resonant Collinearity",
+    "Disk Fragmenter",
+    "Hoof It",
+    "Plutonian Pebbles",
+    "Garden Groups",
+    "Claw Contraption",
+    "Restroom Redoubt",
+    "Warehouse Woes",
+    "Reindeer Maze",
+    "Chronospatial Computer",
+    "RAM Run",
+    "Linen Layout",
+    "Race Condition",
+    "Keypad Conundrum",
+    "Monkey Market",
+    "LAN Party",
+    "Crossed Wires",
+    "Code Chronicle",
+];
+
+/// Answer to a part of a puzzle, days return either numbers or text. Numbers too large for an
+/// `i128` are kept as text rather than truncated
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    i128::try_from(n).map_or_else(|_| Answer::Text(n.to_string()), Answer::Number)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_number!(u32, u64, u128, usize, i32, i64);
+
+impl From<i128> for Answer {
+    fn from(n: i128) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The day is not part of the calendar
+    InvalidDay(u8),
+    /// Puzzles only have a part 1 and a part 2
+    InvalidPart(u8),
+    /// The day is part of the calendar but has not been solved yet
+    NotImplemented(u8),
+    /// The input of the day is malformed
+    Parse(ParseError),
+    /// The input is well-formed but the puzzle has no answer for it
+    NoAnswer,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InvalidDay(day) => {
+                write!(f, "invalid day {}, must be between 1 and {}", day, N_DAYS)
+            }
+            SolveError::InvalidPart(part) => write!(f, "invalid part {}, must be 1 or 2", part),
+            SolveError::NotImplemented(day) => write!(f, "day {} is not implemented", day),
+            SolveError::Parse(e) => write!(f, "{}", e),
+            SolveError::NoAnswer => write!(f, "the input has no answer"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolveError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::Parse(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub day: u8,
+    pub title: &'static str,
+    pub implemented: bool,
+}
+
+impl Puzzle {
+    pub fn url(&self) -> String {
+        format!("https://adventofcode.com/2024/day/{}", self.day)
+    }
+}
+
+type Solver = fn(&str) -> Result<Answer, SolveError>;
+
+/// Register the solvers of the implemented days
+macro_rules! solvers {
+    ($($day:literal),*) => {
+        paste! {
+            #[allow(clippy::zero_prefixed_literal)]
+            const SOLVERS: &[(u8, [Solver; 2])] = &[$(
+                (
+                    $day,
+                    [
+                        |input| Ok([<day $day>]::part1(input)?.into()),
+                        |input| Ok([<day $day>]::part2(input)?.into()),
+                    ],
+                ),
+            )*];
+        }
+    };
+}
+
+solvers!(01, 02, 03, 04, 05, 06, 07, 08, 09, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22);
+
+/// Alternative implementation of a part, which must give the same answer as the part. Tests check
+/// that they agree and benches compare them with the part
+#[derive(Debug, Clone, Copy)]
+pub struct Variant {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    solver: Solver,
+}
+
+impl Variant {
+    pub fn solve(&self, input: &str) -> Result<Answer, SolveError> {
+        (self.solver)(input)
+    }
+}
+
+/// Register alternative implementations, as `(day, part, function of the day module)`
+macro_rules! variants {
+    ($(($day:literal, $part:literal, $function:ident)),* $(,)?) => {
+        paste! {
+            #[allow(clippy::zero_prefixed_literal)]
+            const VARIANTS: &[Variant] = &[$(
+                Variant {
+                    day: $day,
+                    part: $part,
+                    name: stringify!($function),
+                    solver: |input| Ok([<day $day>]::$function(input)?.into()),
+                },
+            )*];
+        }
+    };
+}
+
+variants!(
+    (05, 1, part1_first_implem),
+    (11, 1, part1_cached),
+    (12, 1, part1_flood_fill),
+    (12, 2, part2_flood_fill),
+    (18, 2, part2_dfs)
+);
+
+/// Solve a part of a day, `part` being either 1 or 2
+/// ```
+/// use aoc24::{solve, Answer, SolveError};
+///
+/// let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+/// assert_eq!(solve(1, 1, input), Ok(Answer::Number(11)));
+/// assert_eq!(solve(1, 3, input), Err(SolveError::InvalidPart(3)));
+///
+/// let error = solve(14, 1, "p=0,4 v=3,-3\np=6,3 w=-1,-3\n").unwrap_err();
+/// assert_eq!(error.to_string(), "line 2 col 7: expected `v=`");
+/// ```
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, SolveError> {
+    if day == 0 || day > N_DAYS {
+        return Err(SolveError::InvalidDay(day));
+    }
+    if part == 0 || part > 2 {
+        return Err(SolveError::InvalidPart(part));
+    }
+
+    let (_, solvers) = SOLVERS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .ok_or(SolveError::NotImplemented(day))?;
+
+    solvers[part as usize - 1](input)
+}
+
+/// Metadata of a day, `None` if it is not part of the calendar
+pub fn puzzle(day: u8) -> Option<Puzzle> {
+    (1..=N_DAYS).contains(&day).then(|| Puzzle {
+        day,
+        title: TITLES[day as usize - 1],
+        implemented: SOLVERS.iter().any(|(d, _)| *d == day),
+    })
+}
+
+/// Metadata of every day of the calendar, implemented or not
+pub fn puzzles() -> impl Iterator<Item = Puzzle> {
+    (1..=N_DAYS).flat_map(puzzle)
+}
+
+/// Days that can be passed to [`solve`]
+pub fn implemented_days() -> impl Iterator<Item = u8> {
+    SOLVERS.iter().map(|(day, _)| *day)
+}
+
+/// Alternative implementations of every part
+pub fn variants() -> impl Iterator<Item = Variant> {
+    VARIANTS.iter().copied()
+}
+
+/// Check that the alternative implementations of `part`, named `part1` or `part2` like the
+/// functions of the day modules, give the `expected` answer
+#[cfg(test)]
+pub(crate) fn assert_variants_agree(day: u8, part: &str, input: &str, expected: Answer) {
+    for variant in variants().filter(|v| v.day == day && format!("part{}", v.part) == part) {
+        assert_eq!(
+            variant.solve(input),
+            Ok(expected.clone()),
+            "day{:02}::{} disagrees with {}",
+            day,
+            variant.name,
+            part
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_errors() {
+        assert_eq!(solve(0, 1, ""), Err(SolveError::InvalidDay(0)));
+        assert_eq!(solve(26, 1, ""), Err(SolveError::InvalidDay(26)));
+        assert_eq!(solve(1, 0, ""), Err(SolveError::InvalidPart(0)));
+        assert_eq!(solve(25, 1, ""), Err(SolveError::NotImplemented(25)));
+    }
+
+    #[test]
+    fn text_answers() {
+        let input = indoc::indoc! {"
+            Register A: 729
+            Register B: 0
+            Register C: 0
+
+            Program: 0,1,5,4,3,0
+        "};
+
+        assert_eq!(
+            solve(17, 1, input),
+            Ok(Answer::Text("4,6,3,5,6,3,5,2,1,0".to_string()))
+        );
+    }
+
+    #[test]
+    fn large_answers() {
+        assert_eq!(Answer::from(u64::MAX), Answer::Number(u64::MAX as i128));
+        assert_eq!(Answer::from(i128::MAX as u128), Answer::Number(i128::MAX));
+        assert_eq!(
+            Answer::from(u128::MAX),
+            Answer::Text("340282366920938463463374607431768211455".to_string())
+        );
+    }
+
+    #[test]
+    fn metadata() {
+        assert_eq!(puzzles().count(), N_DAYS as usize);
+        assert_eq!(puzzle(26), None);
+
+        let day01 = puzzle(1).unwrap();
+        assert_eq!(day01.title, "Historian Hysteria");
+        assert!(day01.implemented);
+        assert_eq!(day01.url(), "https://adventofcode.com/2024/day/1");
+
+        assert!(!puzzle(25).unwrap().implemented);
+        assert!(implemented_days().all(|day| puzzle(day).unwrap().implemented));
+        assert!(variants().all(|v| puzzle(v.day).unwrap().implemented && (1..=2).contains(&v.part)));
+    }
+}
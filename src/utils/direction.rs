@@ -0,0 +1,256 @@
+use std::fmt;
+
+/// One of the four cardinal directions, rows going down and columns going right
+/// ```
+/// use aoc24::utils::Direction;
+///
+/// let direction = Direction::from_arrow(b'^').unwrap();
+/// assert_eq!(direction, Direction::North);
+/// assert_eq!(direction.turn_right(), Direction::East);
+/// assert_eq!(direction.turn_left().reverse(), Direction::East);
+/// assert_eq!(direction.delta(), (-1, 0));
+/// assert_eq!(direction.turn_right().to_string(), ">");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise from the north
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    /// Move of one step as `(row, column)`
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Self::North => (-1, 0),
+            Self::East => (0, 1),
+            Self::South => (1, 0),
+            Self::West => (0, -1),
+        }
+    }
+
+    /// Direction of one of `^`, `>`, `v` and `<`
+    pub fn from_arrow(arrow: u8) -> Option<Self> {
+        match arrow {
+            b'^' => Some(Self::North),
+            b'>' => Some(Self::East),
+            b'v' => Some(Self::South),
+            b'<' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> u8 {
+        match self {
+            Self::North => b'^',
+            Self::East => b'>',
+            Self::South => b'v',
+            Self::West => b'<',
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.arrow() as char)
+    }
+}
+
+/// One of the four cardinal directions or the four diagonals between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Clockwise from the north
+    pub const ALL: [Direction8; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    /// Turn of 45° clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turn of 45° counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Move of one step as `(row, column)`
+    pub fn delta(self) -> (i32, i32) {
+        match self {
+            Self::North => (-1, 0),
+            Self::NorthEast => (-1, 1),
+            Self::East => (0, 1),
+            Self::SouthEast => (1, 1),
+            Self::South => (1, 0),
+            Self::SouthWest => (1, -1),
+            Self::West => (0, -1),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Self::ALL[direction as usize * 2]
+    }
+}
+
+/// Set of cardinal directions, stored in a byte
+/// ```
+/// use aoc24::utils::{Direction, DirectionSet};
+///
+/// let mut set = DirectionSet::default();
+/// assert!(set.insert(Direction::West));
+/// assert!(!set.insert(Direction::West));
+/// assert!(set.insert(Direction::North));
+/// assert_eq!(set.iter().collect::<Vec<_>>(), [Direction::North, Direction::West]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirectionSet(u8);
+
+impl DirectionSet {
+    /// Add `direction`, returning whether it was missing
+    pub fn insert(&mut self, direction: Direction) -> bool {
+        let missing = !self.contains(direction);
+        self.0 |= direction.bit();
+        missing
+    }
+
+    /// Remove `direction`, returning whether it was there
+    pub fn remove(&mut self, direction: Direction) -> bool {
+        let present = self.contains(direction);
+        self.0 &= !direction.bit();
+        present
+    }
+
+    pub fn contains(self, direction: Direction) -> bool {
+        self.0 & direction.bit() != 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Directions of the set, clockwise from the north
+    pub fn iter(self) -> impl Iterator<Item = Direction> {
+        Direction::ALL
+            .into_iter()
+            .filter(move |&d| self.contains(d))
+    }
+}
+
+impl FromIterator<Direction> for DirectionSet {
+    fn from_iter<I: IntoIterator<Item = Direction>>(iter: I) -> Self {
+        let mut set = Self::default();
+        iter.into_iter().for_each(|direction| {
+            set.insert(direction);
+        });
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+            assert_eq!(Direction::from_arrow(direction.arrow()), Some(direction));
+
+            let (di, dj) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-di, -dj));
+            assert_eq!(Direction8::from(direction).delta(), (di, dj));
+        }
+        assert_eq!(Direction::from_arrow(b'.'), None);
+
+        for direction in Direction8::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.reverse().reverse(), direction);
+
+            let (di, dj) = direction.delta();
+            assert_eq!(direction.reverse().delta(), (-di, -dj));
+            // Two turns make a right angle
+            let (ri, rj) = direction.turn_right().turn_right().delta();
+            assert_eq!(di * ri + dj * rj, 0);
+        }
+    }
+
+    #[test]
+    fn set() {
+        let mut set = Direction::ALL.into_iter().collect::<DirectionSet>();
+        assert_eq!(set.len(), 4);
+        assert!(set.remove(Direction::East));
+        assert!(!set.remove(Direction::East));
+        assert!(!set.contains(Direction::East));
+        assert_eq!(set.len(), 3);
+
+        Direction::ALL.into_iter().for_each(|direction| {
+            set.remove(direction);
+        });
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use super::{AdjacentPositions, Grid};
+
+/// Partition of the elements `0..len` into disjoint sets, merged with [`DisjointSet::union`].
+/// Finding the set of an element compresses the path to its root, and the set of lower rank is
+/// merged under the other, so that both operations take nearly constant time
+/// ```
+/// use aoc24::utils::DisjointSet;
+///
+/// let mut sets = DisjointSet::new(5);
+/// assert!(sets.union(0, 1));
+/// assert!(sets.union(3, 1));
+/// assert!(!sets.union(0, 3));
+/// assert!(sets.same(0, 3));
+/// assert!(!sets.same(0, 2));
+/// assert_eq!(sets.size(3), 3);
+/// assert_eq!(sets.count(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// Every element of `0..len` in its own set
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// One set per cell of `grid`, cells being united with their cardinal neighbours when
+    /// `matching` holds for both. The cell `(i, j)` is the element `i * width + j`, see
+    /// [`Grid::flat_index`]
+    pub fn from_grid<T>(grid: &Grid<T>, mut matching: impl FnMut(&T, &T) -> bool) -> Self {
+        let mut sets = Self::new(grid.height() * grid.width());
+        let bound = (grid.height(), grid.width());
+        for (position, cell) in grid.cells() {
+            // Looking right and down is enough to see every pair once
+            for next in position.stencil_positions([(0, 1), (1, 0)], bound) {
+                if matching(cell, &grid[next]) {
+                    sets.union(grid.flat_index(position), grid.flat_index(next));
+                }
+            }
+        }
+
+        sets
+    }
+
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Representative of the set of `element`, the same for every element of the set until the
+    /// next union
+    pub fn find(&mut self, mut element: usize) -> usize {
+        // Path halving: every other element on the path is linked to its grandparent
+        while self.parents[element] != element {
+            let grandparent = self.parents[self.parents[element]];
+            self.parents[element] = grandparent;
+            element = grandparent;
+        }
+
+        element
+    }
+
+    /// Merge the sets of `a` and `b`, returning whether they were disjoint
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.count -= 1;
+
+        true
+    }
+
+    /// Whether `a` and `b` are in the same set
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set of `element`
+    pub fn size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    /// Unite the cell of `grid` at `position` with its cardinal neighbours, when `matching` holds
+    /// for both. Cells are numbered like in [`DisjointSet::from_grid`]
+    pub fn union_matching_neighbours<T>(
+        &mut self,
+        grid: &Grid<T>,
+        position: (usize, usize),
+        mut matching: impl FnMut(&T, &T) -> bool,
+    ) {
+        for next in grid.neighbours(position) {
+            if matching(&grid[position], &grid[next]) {
+                self.union(grid.flat_index(position), grid.flat_index(next));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut sets = DisjointSet::new(10);
+        assert_eq!((sets.len(), sets.count()), (10, 10));
+
+        // A long chain, to go through path compression
+        for i in 1..8 {
+            assert!(sets.union(i - 1, i));
+        }
+        assert_eq!(sets.count(), 3);
+        assert_eq!(sets.size(0), 8);
+        assert_eq!(sets.size(9), 1);
+        assert!(sets.same(7, 0));
+        assert!(!sets.same(7, 8));
+        assert_eq!(sets.find(3), sets.find(5));
+
+        assert!(sets.union(9, 8));
+        assert!(sets.union(4, 8));
+        assert!(!sets.union(9, 0));
+        assert_eq!((sets.count(), sets.size(2)), (1, 10));
+
+        assert!(DisjointSet::new(0).is_empty());
+    }
+
+    #[test]
+    fn grid() {
+        let grid = Grid::from_bytes("AAB\nBAB\nBBA\n").unwrap();
+        let mut sets = DisjointSet::from_grid(&grid, |a, b| a == b);
+        assert_eq!(sets.count(), 4);
+        assert_eq!(sets.size(grid.flat_index((0, 0))), 3);
+        assert_eq!(sets.size(grid.flat_index((0, 2))), 2);
+        assert_eq!(sets.size(grid.flat_index((1, 0))), 3);
+        assert!(!sets.same(grid.flat_index((2, 2)), grid.flat_index((1, 1))));
+
+        // Only the `.` cells are united
+        let mut grid = Grid::from_bytes(".#.\n.#.\n").unwrap();
+        let mut sets = DisjointSet::from_grid(&grid, |&a, &b| a == b'.' && b == b'.');
+        assert_eq!(sets.count(), 4);
+        grid[(1, 1)] = b'.';
+        sets.union_matching_neighbours(&grid, (1, 1), |&a, &b| a == b'.' && b == b'.');
+        assert_eq!(sets.count(), 2);
+        assert_eq!(sets.size(grid.flat_index((0, 2))), 5);
+        assert_eq!(sets.size(grid.flat_index((0, 1))), 1);
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+/// Malformed input, located by its 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Error located at the start of `at`, which must be a slice of `input`
+    /// ```
+    /// use aoc24::utils::ParseError;
+    ///
+    /// let input = "p=0,4 v=3,-3\np=6,3 w=-1,-3\n";
+    /// let at = &input[19..];
+    /// let error = ParseError::at(input, at, "`v=`");
+    /// assert_eq!((error.line, error.column), (2, 7));
+    /// assert_eq!(error.to_string(), "line 2 col 7: expected `v=`");
+    /// ```
+    pub fn at(input: &str, at: &str, expected: impl Into<String>) -> Self {
+        let mut offset = (at.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+        }
+    }
+
+    /// Error located right after the last character of `input`
+    pub fn end(input: &str, expected: impl Into<String>) -> Self {
+        Self::at(input, &input[input.len()..], expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} col {}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Largest side of the grids accepted by the days, to reject inputs that would exhaust memory
+pub const MAX_GRID_SIZE: usize = 4096;
+
+/// Height and width of the rectangular grid of `input`, trailing empty lines being ignored. Both
+/// must be between 1 and [`MAX_GRID_SIZE`]
+/// ```
+/// use aoc24::utils::grid_dimensions;
+///
+/// assert_eq!(grid_dimensions("#..\n.#.\n\n"), Ok((2, 3)));
+/// assert_eq!(
+///     grid_dimensions("#..\n.#\n").unwrap_err().to_string(),
+///     "line 2 col 3: expected lines of 3 cells"
+/// );
+/// ```
+pub fn grid_dimensions(input: &str) -> Result<(usize, usize), ParseError> {
+    let width = input.lines().next().map_or(0, str::len);
+    if width == 0 {
+        return Err(ParseError::at(input, input, "a grid"));
+    }
+
+    // Trailing blank lines are ignored, the lines of the grid are checked whole as callers read
+    // them with `input.lines().take(height)`
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+
+    let mut height = 0;
+    for line in input.lines().take(lines) {
+        if height == MAX_GRID_SIZE {
+            return Err(ParseError::at(
+                input,
+                line,
+                format!("at most {MAX_GRID_SIZE} lines"),
+            ));
+        }
+        if line.len() != width {
+            let mut at = width.min(line.len());
+            while !line.is_char_boundary(at) {
+                at -= 1;
+            }
+            let expected = format!("lines of {width} cells");
+            return Err(ParseError::at(input, &line[at..], expected));
+        }
+        if width > MAX_GRID_SIZE {
+            return Err(ParseError::at(
+                input,
+                line,
+                format!("at most {MAX_GRID_SIZE} cells per line"),
+            ));
+        }
+        height += 1;
+    }
+    if height == 0 {
+        // Only whitespace, which is trimmed
+        return Err(ParseError::at(input, input, "a grid"));
+    }
+
+    Ok((height, width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse_number;
+
+    #[test]
+    fn location() {
+        let input = "3   4\n4   x\n";
+
+        assert_eq!(
+            parse_number::<u32>(input, &input[10..11]),
+            Err(ParseError {
+                line: 2,
+                column: 5,
+                expected: "a number".to_string()
+            })
+        );
+        assert_eq!(parse_number::<u32>(input, &input[6..7]), Ok(4));
+
+        let error = ParseError::end(input, "`^`");
+        assert_eq!((error.line, error.column), (3, 1));
+    }
+
+    #[test]
+    fn grid_caps() {
+        let line = ".".repeat(MAX_GRID_SIZE);
+        let input = format!("{line}\n").repeat(MAX_GRID_SIZE);
+        assert_eq!(grid_dimensions(&input), Ok((MAX_GRID_SIZE, MAX_GRID_SIZE)));
+
+        let input = format!("{input}{line}\n");
+        assert_eq!(
+            grid_dimensions(&input).unwrap_err().to_string(),
+            format!(
+                "line {} col 1: expected at most {MAX_GRID_SIZE} lines",
+                MAX_GRID_SIZE + 1
+            )
+        );
+
+        let input = format!("{line}.\n");
+        assert_eq!(
+            grid_dimensions(&input).unwrap_err().to_string(),
+            format!("line 1 col 1: expected at most {MAX_GRID_SIZE} cells per line")
+        );
+
+        assert!(grid_dimensions("").is_err());
+        assert!(grid_dimensions("\r").is_err());
+        assert!(grid_dimensions("  \n").is_err());
+    }
+
+    #[test]
+    fn grid_trailing_whitespace() {
+        assert_eq!(grid_dimensions("16\n66\n\n  \n"), Ok((2, 2)));
+        assert_eq!(grid_dimensions("16\r\n66\r\n"), Ok((2, 2)));
+        assert_eq!(
+            grid_dimensions("16\n66 \t ").unwrap_err().to_string(),
+            "line 2 col 3: expected lines of 2 cells"
+        );
+        assert!(grid_dimensions("16\n66\r\r  ").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use super::{grid_dimensions, AdjacentPositions, ParseError, Point};
+
+/// Rectangular grid of cells, indexed by `(row, column)`. Indexing with a row number gives the
+/// row as a slice, so `grid[i][j]` works too
+/// ```
+/// use aoc24::utils::Grid;
+///
+/// let grid = Grid::from_bytes("#..\n.S.\n").unwrap();
+/// assert_eq!((grid.height(), grid.width()), (2, 3));
+/// assert_eq!(grid.find(|&cell| cell == b'S'), Some((1, 1)));
+/// assert_eq!(grid[(0, 0)], b'#');
+/// assert_eq!(grid[1], *b".S.");
+///
+/// let grid = grid.with_border(b'%');
+/// assert_eq!(grid.to_string(), "%%%%%\n%#..%\n%.S.%\n%%%%%\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    /// Grid of `height` rows of `width` copies of `cell`
+    pub fn new(height: usize, width: usize, cell: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![cell; height * width],
+            height,
+            width,
+        }
+    }
+
+    /// Grid of the lines of `input`, see [`grid_dimensions`]. `cell` reads each byte with its
+    /// position, or returns what was expected instead
+    pub fn parse<E: Into<String>>(
+        input: &str,
+        mut cell: impl FnMut((usize, usize), u8) -> Result<T, E>,
+    ) -> Result<Self, ParseError> {
+        let (height, width) = grid_dimensions(input)?;
+        let mut cells = Vec::with_capacity(height * width);
+
+        for (i, line) in input.lines().take(height).enumerate() {
+            for (j, byte) in line.bytes().enumerate() {
+                match cell((i, j), byte) {
+                    Ok(cell) => cells.push(cell),
+                    Err(expected) => {
+                        let mut at = j;
+                        while !line.is_char_boundary(at) {
+                            at -= 1;
+                        }
+                        return Err(ParseError::at(input, &line[at..], expected));
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            cells,
+            height,
+            width,
+        })
+    }
+
+    /// Same grid surrounded by `sentinel` cells, positions are shifted by one
+    pub fn with_border(self, sentinel: T) -> Self
+    where
+        T: Clone,
+    {
+        let mut bordered = Self::new(self.height + 2, self.width + 2, sentinel);
+        for (i, row) in self.rows().enumerate() {
+            bordered[i + 1][1..=self.width].clone_from_slice(row);
+        }
+
+        bordered
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get(&self, (i, j): (usize, usize)) -> Option<&T> {
+        (i < self.height && j < self.width).then(|| &self.cells[i * self.width + j])
+    }
+
+    pub fn get_mut(&mut self, (i, j): (usize, usize)) -> Option<&mut T> {
+        (i < self.height && j < self.width).then(|| &mut self.cells[i * self.width + j])
+    }
+
+    /// Index of `position` when the cells are numbered row after row
+    pub fn flat_index(&self, (i, j): (usize, usize)) -> usize {
+        i * self.width + j
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Empty grids have no cells to split
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            j < self.width,
+            "column {j} out of a grid of width {}",
+            self.width
+        );
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    /// Cells with their position, row after row
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Positions of the grid, row after row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Position of the first cell matching `predicate`, row after row
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        let index = self.cells.iter().position(predicate)?;
+        Some((index / self.width, index % self.width))
+    }
+
+    /// `position` moved by `delta`, `None` when it leaves the grid
+    pub fn step(
+        &self,
+        position: (usize, usize),
+        delta: impl Into<Point>,
+    ) -> Option<(usize, usize)> {
+        position.step(delta, (self.height, self.width))
+    }
+
+    /// Positions next to `position` in the four cardinal directions clockwise from the north,
+    /// inside the grid
+    pub fn neighbours(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        position.cardinal_positions((self.height, self.width))
+    }
+}
+
+impl Grid<u8> {
+    /// Grid of the bytes of `input`, see [`grid_dimensions`]
+    pub fn from_bytes(input: &str) -> Result<Self, ParseError> {
+        Self::parse(input, |_, byte| Ok::<_, String>(byte))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        &self[i][j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        &mut self[i][j]
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = [T];
+
+    fn index(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.cells[i * self.width..(i + 1) * self.width]
+    }
+}
+
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for &cell in row {
+                write!(f, "{}", cell.into())?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input = "12\n34\n\n";
+        let grid = Grid::parse(input, |_, byte| {
+            (byte as char).to_digit(10).ok_or("a digit")
+        });
+        assert_eq!(grid.as_ref().map(Grid::height), Ok(2));
+        let grid = grid.unwrap();
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(
+            grid.cells().collect::<Vec<_>>(),
+            [((0, 0), &1), ((0, 1), &2), ((1, 0), &3), ((1, 1), &4)]
+        );
+
+        let error = Grid::parse("12\n3x\n", |_, byte| {
+            (byte as char).to_digit(10).ok_or("a digit")
+        });
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 2 col 2: expected a digit"
+        );
+
+        let error = Grid::parse("1é\n", |_, byte| {
+            (byte as char).to_digit(10).ok_or("a digit")
+        });
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 1 col 2: expected a digit"
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let mut grid = Grid::new(2, 3, 0);
+        grid[(1, 2)] = 5;
+        *grid.get_mut((0, 1)).unwrap() = 7;
+        assert_eq!(grid.get((1, 2)), Some(&5));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[0, 7, 0], [0, 0, 5]]);
+
+        assert_eq!(
+            grid.neighbours((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours((1, 1)).collect::<Vec<_>>(),
+            [(0, 1), (1, 2), (1, 0)]
+        );
+
+        let empty = Grid::new(0, 0, b'.');
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.find(|_| true), None);
+        assert_eq!(empty.with_border(b'%').to_string(), "%%\n%%\n");
+    }
+}
@@ -1,90 +1,347 @@
-pub trait ManhattanDistance {
-    /// Returns an iterator of coordinates within a manhattan distance of `distance` from `self`, excluding out of bound coordinates.
-    /// `min_bound` and `distance` is inclusive and `max_bound` is exclusive.
-    /// ```
-    /// use aoc24::utils::ManhattanDistance;
-    ///
-    /// let result = (1, 1).at_manhattan_distance(1, 0, 4).collect::<Vec<_>>();
-    /// assert_eq!(result, vec![(1, 2), (2, 1), (1, 0), (0, 1)]);
-    ///
-    /// let result = (1, 1).at_manhattan_distance(2, 0, 4).collect::<Vec<_>>();
-    /// assert_eq!(result, vec![(1, 3), (3, 1), (2, 2), (2, 0), (0, 0), (0, 2)]);
-    /// ```
+use std::ops::Range;
+
+/// Positions at a manhattan distance from a position, skipping the ones out of bounds. Bounds
+/// are one range per axis, so grids don't have to be square
+///
+/// Positions come ring after ring of increasing distance, starting with the position itself at
+/// distance 0. Each ring of a 2-D position goes clockwise from its northernmost position, rows
+/// being the first coordinate. A 3-D ring goes layer after layer along the first axis
+/// ```
+/// use aoc24::utils::ManhattanDistance;
+///
+/// let bounds = (0..3, 0..4);
+/// let result = (1usize, 1).at_manhattan_distance(1, bounds.clone()).collect::<Vec<_>>();
+/// assert_eq!(result, vec![(0, 1), (1, 2), (2, 1), (1, 0)]);
+///
+/// let result = (1usize, 1).at_manhattan_distance(2, bounds.clone()).collect::<Vec<_>>();
+/// assert_eq!(result, vec![(0, 2), (1, 3), (2, 2), (2, 0), (0, 0)]);
+///
+/// let result = (1usize, 1).within_manhattan_distance(1, bounds.clone()).collect::<Vec<_>>();
+/// assert_eq!(result, vec![(1, 1), (0, 1), (1, 2), (2, 1), (1, 0)]);
+/// assert_eq!((1usize, 1).count_within_manhattan_distance(2, bounds), 10);
+///
+/// let result = (0isize, 0isize).at_manhattan_distance(1, (-5..5, 0..5));
+/// assert_eq!(result.collect::<Vec<_>>(), vec![(-1, 0), (0, 1), (1, 0)]);
+/// ```
+pub trait ManhattanDistance: Copy {
+    /// Range of each axis, the start is inclusive and the end is exclusive
+    type Bounds: Clone;
+
+    /// Positions at exactly `distance`
     fn at_manhattan_distance(
-        &self,
+        self,
         distance: usize,
-        min_bound: usize,
-        max_bound: usize,
-    ) -> impl Iterator<Item = (usize, usize)>;
-
-    /// Returns an iterator of coordinates within a manhattan distance of `distance` from `self`, excluding out of bound coordinates.
-    /// `min_bound` and `distance` is inclusive and `max_bound` is exclusive.
-    /// ```
-    /// use aoc24::utils::ManhattanDistance;
-    ///
-    /// let result = (1, 1)
-    ///     .within_manhattan_distance(2, 0, 4)
-    ///     .collect::<Vec<_>>();
-    /// assert_eq!(
-    ///     result,
-    ///     vec![
-    ///         (1, 2),
-    ///         (2, 1),
-    ///         (1, 0),
-    ///         (0, 1),
-    ///         (1, 3),
-    ///         (3, 1),
-    ///         (2, 2),
-    ///         (2, 0),
-    ///         (0, 0),
-    ///         (0, 2)
-    ///     ]
-    /// );
-    /// ```
+        bounds: Self::Bounds,
+    ) -> impl Iterator<Item = Self>;
+
+    /// Positions at a distance up to `distance`, `distance` is inclusive
     fn within_manhattan_distance(
-        &self,
+        self,
         distance: usize,
-        min_bound: usize,
-        max_bound: usize,
-    ) -> impl Iterator<Item = (usize, usize)>;
+        bounds: Self::Bounds,
+    ) -> impl Iterator<Item = Self> {
+        (0..=distance).flat_map(move |d| self.at_manhattan_distance(d, bounds.clone()))
+    }
+
+    /// Number of positions at exactly `distance`, without going through them
+    fn count_at_manhattan_distance(self, distance: usize, bounds: Self::Bounds) -> usize {
+        let inner = distance.checked_sub(1).map_or(0, |inner| {
+            self.count_within_manhattan_distance(inner, bounds.clone())
+        });
+        self.count_within_manhattan_distance(distance, bounds) - inner
+    }
+
+    /// Number of positions at a distance up to `distance`, without going through them
+    fn count_within_manhattan_distance(self, distance: usize, bounds: Self::Bounds) -> usize;
 }
 
-impl ManhattanDistance for (usize, usize) {
-    fn at_manhattan_distance(
-        &self,
+/// Positions at a Chebyshev distance from a position, where diagonal moves count as one step,
+/// skipping the ones out of bounds
+///
+/// Positions come ring after ring of increasing distance, starting with the position itself at
+/// distance 0. Each ring is a square going clockwise from its north-west corner
+/// ```
+/// use aoc24::utils::ChebyshevDistance;
+///
+/// let result = (0usize, 0).at_chebyshev_distance(1, (0..3, 0..3)).collect::<Vec<_>>();
+/// assert_eq!(result, vec![(0, 1), (1, 1), (1, 0)]);
+/// assert_eq!((2usize, 2).count_within_chebyshev_distance(1, (0..3, 0..4)), 6);
+/// ```
+pub trait ChebyshevDistance: Copy {
+    /// Range of each axis, the start is inclusive and the end is exclusive
+    type Bounds: Clone;
+
+    /// Positions at exactly `distance`
+    fn at_chebyshev_distance(
+        self,
         distance: usize,
-        min_bound: usize,
-        max_bound: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
-        let distance = distance as isize;
-        let min_bound = min_bound as isize;
-        let max_bound = max_bound as isize;
-        (0..distance)
-            .flat_map(move |offset| {
-                let inv_offset = distance - offset;
-                let x = self.0 as isize;
-                let y = self.1 as isize;
-
-                [
-                    (x + offset, y + inv_offset),
-                    (x + inv_offset, y - offset),
-                    (x - offset, y - inv_offset),
-                    (x - inv_offset, y + offset),
-                ]
-                .into_iter()
-            })
-            .filter_map(move |(x, y)| {
-                (x >= min_bound && x < max_bound && y >= min_bound && y < max_bound)
-                    .then_some((x as usize, y as usize))
-            })
-    }
+        bounds: Self::Bounds,
+    ) -> impl Iterator<Item = Self>;
 
-    fn within_manhattan_distance(
-        &self,
+    /// Positions at a distance up to `distance`, `distance` is inclusive
+    fn within_chebyshev_distance(
+        self,
         distance: usize,
-        min_bound: usize,
-        max_bound: usize,
-    ) -> impl Iterator<Item = (usize, usize)> {
-        (0..=distance).flat_map(move |d| self.at_manhattan_distance(d, min_bound, max_bound))
+        bounds: Self::Bounds,
+    ) -> impl Iterator<Item = Self> {
+        (0..=distance).flat_map(move |d| self.at_chebyshev_distance(d, bounds.clone()))
+    }
+
+    /// Number of positions at a distance up to `distance`, without going through them
+    fn count_within_chebyshev_distance(self, distance: usize, bounds: Self::Bounds) -> usize;
+}
+
+/// Ring around `(x, y)`, clockwise from its northernmost position
+fn manhattan_ring((x, y): (isize, isize), distance: isize) -> impl Iterator<Item = (isize, isize)> {
+    // Start of each side of the diamond, and the step along it
+    let sides = [
+        (-distance, 0, 1, 1),
+        (0, distance, 1, -1),
+        (distance, 0, -1, -1),
+        (0, -distance, -1, 1),
+    ];
+
+    (distance == 0)
+        .then_some((x, y))
+        .into_iter()
+        .chain(sides.into_iter().flat_map(move |(x0, y0, dx, dy)| {
+            (0..distance).map(move |k| (x + x0 + k * dx, y + y0 + k * dy))
+        }))
+}
+
+/// Ring around `(x, y)`, clockwise from its north-west corner
+fn chebyshev_ring((x, y): (isize, isize), distance: isize) -> impl Iterator<Item = (isize, isize)> {
+    // Corners of the square, and the step along the side starting there
+    let sides = [
+        (-distance, -distance, 0, 1),
+        (-distance, distance, 1, 0),
+        (distance, distance, 0, -1),
+        (distance, -distance, -1, 0),
+    ];
+
+    (distance == 0)
+        .then_some((x, y))
+        .into_iter()
+        .chain(sides.into_iter().flat_map(move |(x0, y0, dx, dy)| {
+            (0..2 * distance).map(move |k| (x + x0 + k * dx, y + y0 + k * dy))
+        }))
+}
+
+/// Number of values of `from..=to` inside `range`
+fn overlap(from: isize, to: isize, range: &Range<isize>) -> usize {
+    (to.min(range.end - 1) - from.max(range.start) + 1).max(0) as usize
+}
+
+/// Number of positions within `distance` of `(x, y)`, one row at a time
+fn count_within_manhattan(
+    (x, y): (isize, isize),
+    distance: isize,
+    (rows, columns): &(Range<isize>, Range<isize>),
+) -> usize {
+    ((x - distance).max(rows.start)..(x + distance + 1).min(rows.end))
+        .map(|row| {
+            let remaining = distance - (row - x).abs();
+            overlap(y - remaining, y + remaining, columns)
+        })
+        .sum()
+}
+
+/// Same range as `isize`, grids are much smaller than `isize::MAX`
+fn signed<T: TryInto<isize>>(range: Range<T>) -> Range<isize> {
+    let start = range.start.try_into().unwrap_or(isize::MAX);
+    let end = range.end.try_into().unwrap_or(isize::MAX);
+    start..end
+}
+
+/// Implementations for 2-D and 3-D positions of `$t`, computed with `isize`
+macro_rules! impl_distances {
+    ($($t:ty),*) => {
+        $(
+            impl ManhattanDistance for ($t, $t) {
+                type Bounds = (Range<$t>, Range<$t>);
+
+                fn at_manhattan_distance(
+                    self,
+                    distance: usize,
+                    (rows, columns): Self::Bounds,
+                ) -> impl Iterator<Item = Self> {
+                    let (rows, columns) = (signed(rows), signed(columns));
+                    manhattan_ring((self.0 as isize, self.1 as isize), distance as isize)
+                        .filter(move |(x, y)| rows.contains(x) && columns.contains(y))
+                        .map(|(x, y)| (x as $t, y as $t))
+                }
+
+                fn count_within_manhattan_distance(
+                    self,
+                    distance: usize,
+                    (rows, columns): Self::Bounds,
+                ) -> usize {
+                    count_within_manhattan(
+                        (self.0 as isize, self.1 as isize),
+                        distance as isize,
+                        &(signed(rows), signed(columns)),
+                    )
+                }
+            }
+
+            impl ManhattanDistance for ($t, $t, $t) {
+                type Bounds = (Range<$t>, Range<$t>, Range<$t>);
+
+                fn at_manhattan_distance(
+                    self,
+                    distance: usize,
+                    (xs, ys, zs): Self::Bounds,
+                ) -> impl Iterator<Item = Self> {
+                    let (xs, ys, zs) = (signed(xs), signed(ys), signed(zs));
+                    let (x, y, z) = (self.0 as isize, self.1 as isize, self.2 as isize);
+                    let distance = distance as isize;
+
+                    ((x - distance).max(xs.start)..(x + distance + 1).min(xs.end))
+                        .flat_map(move |layer| {
+                            manhattan_ring((y, z), distance - (layer - x).abs())
+                                .map(move |(y, z)| (layer, y, z))
+                        })
+                        .filter(move |(_, y, z)| ys.contains(y) && zs.contains(z))
+                        .map(|(x, y, z)| (x as $t, y as $t, z as $t))
+                }
+
+                fn count_within_manhattan_distance(
+                    self,
+                    distance: usize,
+                    (xs, ys, zs): Self::Bounds,
+                ) -> usize {
+                    let (xs, bounds) = (signed(xs), (signed(ys), signed(zs)));
+                    let (x, y, z) = (self.0 as isize, self.1 as isize, self.2 as isize);
+                    let distance = distance as isize;
+
+                    ((x - distance).max(xs.start)..(x + distance + 1).min(xs.end))
+                        .map(|layer| {
+                            count_within_manhattan((y, z), distance - (layer - x).abs(), &bounds)
+                        })
+                        .sum()
+                }
+            }
+
+            impl ChebyshevDistance for ($t, $t) {
+                type Bounds = (Range<$t>, Range<$t>);
+
+                fn at_chebyshev_distance(
+                    self,
+                    distance: usize,
+                    (rows, columns): Self::Bounds,
+                ) -> impl Iterator<Item = Self> {
+                    let (rows, columns) = (signed(rows), signed(columns));
+                    chebyshev_ring((self.0 as isize, self.1 as isize), distance as isize)
+                        .filter(move |(x, y)| rows.contains(x) && columns.contains(y))
+                        .map(|(x, y)| (x as $t, y as $t))
+                }
+
+                fn count_within_chebyshev_distance(
+                    self,
+                    distance: usize,
+                    (rows, columns): Self::Bounds,
+                ) -> usize {
+                    let (x, y) = (self.0 as isize, self.1 as isize);
+                    let distance = distance as isize;
+
+                    overlap(x - distance, x + distance, &signed(rows))
+                        * overlap(y - distance, y + distance, &signed(columns))
+                }
+            }
+        )*
+
+    };
+}
+
+impl_distances!(usize, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rings() {
+        let bounds = (0..100, 0..100);
+        for distance in 0..10 {
+            let ring = (50usize, 50)
+                .at_manhattan_distance(distance, bounds.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(ring.len(), (4 * distance).max(1));
+            assert!(ring
+                .iter()
+                .all(|&(x, y)| x.abs_diff(50) + y.abs_diff(50) == distance));
+
+            let ring = (50usize, 50)
+                .at_chebyshev_distance(distance, bounds.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(ring.len(), (8 * distance).max(1));
+            assert!(ring
+                .iter()
+                .all(|&(x, y)| x.abs_diff(50).max(y.abs_diff(50)) == distance));
+        }
+
+        let ring = (0isize, 0isize, 0isize)
+            .at_manhattan_distance(1, (-1..2, -1..2, -1..2))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ring,
+            [
+                (-1, 0, 0),
+                (0, -1, 0),
+                (0, 0, 1),
+                (0, 1, 0),
+                (0, 0, -1),
+                (1, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn counts() {
+        let bounds = (0..4, 0..7);
+        for position in [(0usize, 0), (2, 3), (3, 6), (1, 5)] {
+            for distance in 0..12 {
+                assert_eq!(
+                    position.count_within_manhattan_distance(distance, bounds.clone()),
+                    position
+                        .within_manhattan_distance(distance, bounds.clone())
+                        .count()
+                );
+                assert_eq!(
+                    position.count_at_manhattan_distance(distance, bounds.clone()),
+                    position
+                        .at_manhattan_distance(distance, bounds.clone())
+                        .count()
+                );
+                assert_eq!(
+                    position.count_within_chebyshev_distance(distance, bounds.clone()),
+                    position
+                        .within_chebyshev_distance(distance, bounds.clone())
+                        .count()
+                );
+            }
+        }
+
+        let bounds = (-2..3, 0..2, -5..0);
+        for distance in 0..8 {
+            assert_eq!(
+                (0isize, 1, -1).count_within_manhattan_distance(distance, bounds.clone()),
+                (0isize, 1, -1)
+                    .within_manhattan_distance(distance, bounds.clone())
+                    .count()
+            );
+        }
+
+        // Empty bounds
+        assert_eq!(
+            (0usize, 0).count_within_manhattan_distance(3, (0..0, 0..5)),
+            0
+        );
+        assert_eq!(
+            (0usize, 0)
+                .within_chebyshev_distance(3, (0..5, 0..0))
+                .count(),
+            0
+        );
     }
 }
@@ -0,0 +1,127 @@
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+/// Hits and misses of a [`Memo`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Cache of the results of a recursive function, counting its hits and misses. The statistics
+/// are logged at the debug level when the cache is dropped.
+/// ```
+/// use aoc24::utils::{Memo, MemoStats};
+///
+/// fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     if n < 2 {
+///         return n;
+///     }
+///     memo.get_or_compute(n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo))
+/// }
+///
+/// let mut memo = Memo::new();
+/// assert_eq!(fibonacci(50, &mut memo), 12586269025);
+/// assert_eq!(memo.stats(), MemoStats { hits: 47, misses: 49 });
+/// ```
+#[derive(Debug)]
+pub struct Memo<K, V, S = RandomState> {
+    cache: HashMap<K, V, S>,
+    stats: MemoStats,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone, S: BuildHasher> Memo<K, V, S> {
+    /// Empty cache hashing its keys with `hasher`
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            cache: HashMap::with_hasher(hasher),
+            stats: MemoStats::default(),
+        }
+    }
+
+    /// Cached value of `key`, or the value computed by `compute` which can recurse with the cache
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+
+        value
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K, V, S> Drop for Memo<K, V, S> {
+    fn drop(&mut self) {
+        let MemoStats { hits, misses } = self.stats;
+        if hits + misses > 0 {
+            tracing::debug!(hits, misses, entries = self.cache.len(), "memo dropped");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    /// Ways to climb `steps` stairs one or two at a time
+    fn climb<S: BuildHasher>(steps: u64, memo: &mut Memo<u64, u64, S>) -> u64 {
+        if steps <= 1 {
+            return 1;
+        }
+        memo.get_or_compute(steps, |memo| {
+            climb(steps - 1, memo) + climb(steps - 2, memo)
+        })
+    }
+
+    #[test]
+    fn stats() {
+        let mut memo = Memo::new();
+        assert!(memo.is_empty());
+
+        assert_eq!(climb(10, &mut memo), 89);
+        assert_eq!(memo.stats(), MemoStats { hits: 7, misses: 9 });
+        assert_eq!(memo.len(), 9);
+
+        // Everything is already known
+        assert_eq!(climb(10, &mut memo), 89);
+        assert_eq!(memo.stats(), MemoStats { hits: 8, misses: 9 });
+    }
+
+    #[test]
+    fn hasher() {
+        let mut memo = Memo::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+        assert_eq!(climb(30, &mut memo), 1346269);
+        assert_eq!(memo.len(), 29);
+    }
+}
@@ -1,6 +1,26 @@
+pub use direction::*;
+pub use disjoint_set::*;
+pub use error::*;
+pub use grid::*;
 pub use manhattan::*;
+pub use memo::*;
+pub use neighbours::*;
+pub use parallel::*;
+pub use parse::*;
+pub use point::*;
+pub use search::*;
 
+mod direction;
+mod disjoint_set;
+mod error;
+mod grid;
 mod manhattan;
+mod memo;
+mod neighbours;
+mod parallel;
+mod parse;
+mod point;
+mod search;
 
 #[macro_export]
 macro_rules! get_day_input {
@@ -20,33 +40,68 @@ macro_rules! get_day_input {
 #[cfg(test)]
 pub use get_day_input;
 
-pub fn number_of_digits_u64(n: u64) -> u32 {
-    (n as f64).log10().floor() as u32 + 1
-}
-
-pub trait CoordinateAddIter<const N: usize> {
-    /// Does not check for out of bounds
-    fn coord_add_iter(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)>;
-}
+/// Tests of a day: the answers of the examples, then the answers of the real input. Tests of the
+/// real input are ignored when `inputs/dayNN.txt` is missing or empty, see `build.rs`. The
+/// alternative implementations registered in `solve.rs` must give the same answers
+/// ```ignore
+/// crate::utils::day_tests! {
+///     day: 01,
+///     examples: {
+///         part1(EXAMPLE) => 11,
+///         part2(EXAMPLE) => 31,
+///     },
+///     input: {
+///         part1 => 936063,
+///         part2 => 23150395,
+///     },
+/// }
+/// ```
+#[cfg(test)]
+macro_rules! day_tests {
+    (
+        day: $day:literal,
+        $(examples: {
+            $($example_part:ident($example:expr) => $example_expected:expr),* $(,)?
+        },)?
+        input: {
+            $($part:ident => $expected:expr),* $(,)?
+        } $(,)?
+    ) => {
+        $(
+            #[test]
+            #[allow(clippy::zero_prefixed_literal)]
+            fn example() {
+                $(
+                    let output = $example_part($example).unwrap();
+                    assert_eq!(output, $example_expected);
+                    let part = stringify!($example_part);
+                    crate::assert_variants_agree($day, part, $example, output.into());
+                )*
+            }
+        )?
 
-impl<const N: usize> CoordinateAddIter<N> for [(i32, i32); N] {
-    fn coord_add_iter(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
-        self.iter()
-            .map(move |(dx, dy)| ((x as i32 + dx) as usize, (y as i32 + dy) as usize))
-    }
+        paste::paste! {
+            $(
+                #[test]
+                #[cfg_attr(not([<input_day $day>]), ignore = "missing input")]
+                #[allow(clippy::zero_prefixed_literal)]
+                fn [<run_ $part>]() {
+                    let input = crate::utils::get_day_input!();
+                    let output = $part(&input).unwrap();
+                    println!("{}: {}", stringify!($part), output);
+                    assert_eq!(output, $expected);
+                    crate::assert_variants_agree($day, stringify!($part), &input, output.into());
+                }
+            )*
+        }
+    };
 }
 
-pub const CARDINAL_DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-
-pub trait CoordinateAdd {
-    /// Does not check for out of bounds
-    fn coord_add(&self, coord: (usize, usize)) -> (usize, usize);
-}
+#[cfg(test)]
+pub(crate) use day_tests;
 
-impl CoordinateAdd for (i32, i32) {
-    fn coord_add(&self, (x, y): (usize, usize)) -> (usize, usize) {
-        ((x as i32 + self.0) as usize, (y as i32 + self.1) as usize)
-    }
+pub fn number_of_digits_u64(n: u64) -> u32 {
+    (n as f64).log10().floor() as u32 + 1
 }
 
 #[cfg(test)]
@@ -67,17 +122,4 @@ mod tests {
         assert_eq!(number_of_digits_u64(100456000), 9);
         assert_eq!(number_of_digits_u64(1000001230), 10);
     }
-
-    #[test]
-    fn coord_diff() {
-        let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-        itertools::assert_equal(
-            directions.coord_add_iter(1, 1),
-            vec![(1, 2), (2, 1), (1, 0), (0, 1)],
-        );
-        itertools::assert_equal(
-            directions.coord_add_iter(2, 1),
-            vec![(2, 2), (3, 1), (2, 0), (1, 1)],
-        );
-    }
 }
@@ -0,0 +1,170 @@
+use super::{Direction, Point};
+
+/// Offsets of the eight adjacent positions, row after row
+const ADJACENT: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(-1, 0),
+    Point::new(-1, 1),
+    Point::new(0, -1),
+    Point::new(0, 1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+];
+
+/// Positions around a position of a `(height, width)` rectangle, the bound is exclusive and
+/// positions outside of it are skipped
+/// ```
+/// use aoc24::utils::AdjacentPositions;
+///
+/// let bound = (2, 3);
+/// assert_eq!((0, 0).cardinal_positions(bound).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+/// assert_eq!((1, 2).adjacent_positions(bound).count(), 3);
+/// assert_eq!((0, 2).step((1, 0), bound), Some((1, 2)));
+/// assert_eq!((0, 2).step((0, 1), bound), None);
+///
+/// // Moves of a knight
+/// let knight = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+/// assert_eq!((0, 0).stencil_positions(knight, (3, 3)).collect::<Vec<_>>(), [(1, 2), (2, 1)]);
+/// ```
+pub trait AdjacentPositions: Sized {
+    /// `self` moved by `delta`, `None` when it leaves the bound
+    fn step(self, delta: impl Into<Point>, bound: Self) -> Option<Self>;
+
+    /// Positions moved by each offset of `stencil`, in the order of the stencil
+    fn stencil_positions<D: Into<Point>>(
+        self,
+        stencil: impl IntoIterator<Item = D>,
+        bound: Self,
+    ) -> impl Iterator<Item = Self>;
+
+    /// Positions in the four cardinal directions, clockwise from the north
+    fn cardinal_positions(self, bound: Self) -> impl Iterator<Item = Self> {
+        self.stencil_positions(Direction::ALL, bound)
+    }
+
+    /// Positions in the four cardinal directions and the four diagonals, row after row
+    fn adjacent_positions(self, bound: Self) -> impl Iterator<Item = Self> {
+        self.stencil_positions(ADJACENT, bound)
+    }
+}
+
+impl AdjacentPositions for (usize, usize) {
+    fn step(self, delta: impl Into<Point>, bound: Self) -> Option<Self> {
+        let moved = Point::try_from(self)
+            .ok()?
+            .bounded_add(delta.into(), bound)?;
+        // Inside the bound, so not negative
+        Some((moved.x as usize, moved.y as usize))
+    }
+
+    fn stencil_positions<D: Into<Point>>(
+        self,
+        stencil: impl IntoIterator<Item = D>,
+        bound: Self,
+    ) -> impl Iterator<Item = Self> {
+        stencil
+            .into_iter()
+            .filter_map(move |delta| self.step(delta, bound))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// . . .
+    /// . X .
+    /// . . .
+    fn center() {
+        let bound = (3, 3);
+        let positions = (1, 1).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    /// X . .
+    /// . . .
+    /// . . .
+    fn top_left() {
+        let bound = (3, 3);
+        let positions = (0, 0).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    /// . . .
+    /// . . .
+    /// . . X
+    fn bottom_right() {
+        let bound = (3, 3);
+        let positions = (2, 2).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 1), (1, 2), (2, 1)]);
+    }
+
+    #[test]
+    /// . . .
+    /// . . .
+    /// . X .
+    fn bottom() {
+        let bound = (3, 3);
+        let positions = (2, 1).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 2)]);
+    }
+
+    #[test]
+    /// . X .
+    /// . . .
+    /// . . .
+    fn top() {
+        let bound = (3, 3);
+        let positions = (0, 1).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 0), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    /// . . .
+    /// X . .
+    /// . . .
+    fn left() {
+        let bound = (3, 3);
+        let positions = (1, 0).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 0), (0, 1), (1, 1), (2, 0), (2, 1)]);
+    }
+
+    #[test]
+    /// . . .
+    /// . . X
+    /// . . .
+    fn right() {
+        let bound = (3, 3);
+        let positions = (1, 2).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 1), (0, 2), (1, 1), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    /// . . . .
+    /// . . . X
+    fn rectangle() {
+        let bound = (2, 4);
+        let positions = (1, 3).adjacent_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 2), (0, 3), (1, 2)]);
+
+        let positions = (1, 3).cardinal_positions(bound).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(0, 3), (1, 2)]);
+        assert_eq!((0, 0).cardinal_positions((1, 1)).count(), 0);
+    }
+}
@@ -0,0 +1,73 @@
+//! Helpers running over the items of a slice, across threads with the `parallel` feature and one
+//! after the other without it. The results do not depend on how the items are split.
+use std::iter::Sum;
+
+/// Sum of `f` over the items
+#[cfg(feature = "parallel")]
+pub fn par_sum<T: Sync, S: Send + Sum>(items: &[T], f: impl Fn(&T) -> S + Sync + Send) -> S {
+    use rayon::prelude::*;
+
+    items.par_iter().map(f).sum()
+}
+
+/// Sum of `f` over the items
+#[cfg(not(feature = "parallel"))]
+pub fn par_sum<T: Sync, S: Send + Sum>(items: &[T], f: impl Fn(&T) -> S + Sync + Send) -> S {
+    items.iter().map(f).sum()
+}
+
+/// Fold the items into accumulators created by `init`, then combine the accumulators with
+/// `merge`, which must be associative and commutative for the result to be deterministic
+#[cfg(feature = "parallel")]
+pub fn par_fold<T: Sync, A: Send>(
+    items: &[T],
+    init: impl Fn() -> A + Sync + Send,
+    fold: impl Fn(A, &T) -> A + Sync + Send,
+    merge: impl Fn(A, A) -> A + Sync + Send,
+) -> A {
+    use rayon::prelude::*;
+
+    items.par_iter().fold(&init, fold).reduce(&init, merge)
+}
+
+/// Fold the items into accumulators created by `init`, then combine the accumulators with
+/// `merge`, which must be associative and commutative for the result to be deterministic
+#[cfg(not(feature = "parallel"))]
+pub fn par_fold<T: Sync, A: Send>(
+    items: &[T],
+    init: impl Fn() -> A + Sync + Send,
+    fold: impl Fn(A, &T) -> A + Sync + Send,
+    _merge: impl Fn(A, A) -> A + Sync + Send,
+) -> A {
+    items.iter().fold(init(), fold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum() {
+        let items = (1..=1000).collect::<Vec<u64>>();
+        assert_eq!(par_sum(&items, |i| i * 2), 1001000);
+        assert_eq!(par_sum(&[] as &[u64], |i| *i), 0);
+    }
+
+    #[test]
+    fn fold() {
+        let items = (0..1000).collect::<Vec<usize>>();
+        let counts = par_fold(
+            &items,
+            || vec![0; 3],
+            |mut counts, i| {
+                counts[i % 3] += 1;
+                counts
+            },
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        );
+        assert_eq!(counts, vec![334, 333, 333]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::iter;
+
+use super::ParseError;
+
+/// Integers read from their ASCII digits
+pub trait Integer: Sized {
+    /// Value of `bytes`: digits preceded by an optional `+`, or `-` for signed types. `None` when
+    /// there are no digits, another byte, or the value overflows
+    /// ```
+    /// use aoc24::utils::Integer;
+    ///
+    /// assert_eq!(u8::from_digits(b"255"), Some(255));
+    /// assert_eq!(i8::from_digits(b"-128"), Some(-128));
+    /// assert_eq!(u8::from_digits(b"256"), None);
+    /// assert_eq!(u32::from_digits(b"-3"), None);
+    /// assert_eq!(u32::from_digits(b"3 "), None);
+    /// ```
+    fn from_digits(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($signed:literal, $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn from_digits(bytes: &[u8]) -> Option<Self> {
+                    let (negative, digits) = match bytes {
+                        [b'-', digits @ ..] if $signed => (true, digits),
+                        [b'+', digits @ ..] => (false, digits),
+                        digits => (false, digits),
+                    };
+                    if digits.is_empty() {
+                        return None;
+                    }
+
+                    digits.iter().try_fold(0 as $t, |n, &byte| {
+                        let digit = byte.wrapping_sub(b'0');
+                        if digit > 9 {
+                            return None;
+                        }
+                        // Negative numbers are built downwards, to reach the minimum
+                        let n = n.checked_mul(10)?;
+                        if negative {
+                            n.checked_sub(digit as $t)
+                        } else {
+                            n.checked_add(digit as $t)
+                        }
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(false, u8, u16, u32, u64, u128, usize);
+impl_integer!(true, i8, i16, i32, i64, i128, isize);
+
+/// Parse `s`, which must be a slice of `input`, as a number
+pub fn parse_number<T: Integer>(input: &str, s: &str) -> Result<T, ParseError> {
+    T::from_digits(s.as_bytes()).ok_or_else(|| ParseError::at(input, s, "a number"))
+}
+
+/// Every integer of `line`, which must be a slice of `input`, skipping the text around them. A
+/// `-` right before digits is read as a sign, an error for unsigned types
+/// ```
+/// use aoc24::utils::integers;
+///
+/// let line = "p=0,4 v=3,-3";
+/// let numbers = integers::<i32>(line, line).collect::<Result<Vec<_>, _>>();
+/// assert_eq!(numbers, Ok(vec![0, 4, 3, -3]));
+///
+/// let error = integers::<u32>(line, line).nth(3).unwrap().unwrap_err();
+/// assert_eq!(error.to_string(), "line 1 col 11: expected a number");
+/// ```
+pub fn integers<'a, T: Integer>(
+    input: &'a str,
+    line: &'a str,
+) -> impl Iterator<Item = Result<T, ParseError>> + 'a {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+
+        let start = if i > 0 && bytes[i - 1] == b'-' {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        Some(parse_number(input, &line[start..i]))
+    })
+}
+
+/// The `N` integers of `line`, see [`integers`]. `expected` describes the line when it holds
+/// another number of integers
+/// ```
+/// use aoc24::utils::integer_array;
+///
+/// let input = "3,4\n5\n";
+/// assert_eq!(integer_array(input, &input[..3], "two numbers"), Ok([3, 4]));
+///
+/// let error = integer_array::<u32, 2>(input, &input[4..5], "two numbers").unwrap_err();
+/// assert_eq!(error.to_string(), "line 2 col 1: expected two numbers");
+/// ```
+pub fn integer_array<T: Integer, const N: usize>(
+    input: &str,
+    line: &str,
+    expected: &str,
+) -> Result<[T; N], ParseError> {
+    let numbers = integers(input, line).collect::<Result<Vec<_>, _>>()?;
+    numbers
+        .try_into()
+        .map_err(|_| ParseError::at(input, line, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(u64::from_digits(b"18446744073709551615"), Some(u64::MAX));
+        assert_eq!(u64::from_digits(b"18446744073709551616"), None);
+        assert_eq!(i64::from_digits(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(i64::from_digits(b"9223372036854775808"), None);
+        assert_eq!(i32::from_digits(b"+7"), Some(7));
+        assert_eq!(u32::from_digits(b"-0"), None);
+        assert_eq!(u32::from_digits(b""), None);
+        assert_eq!(i32::from_digits(b"-"), None);
+        assert_eq!(i32::from_digits(b"--1"), None);
+    }
+
+    #[test]
+    fn separated() {
+        let input = "Button A: X+94, Y+34\nPrize: X=8400, Y=5400\n";
+        let line = input.lines().nth(1).unwrap();
+        let numbers = integers::<u64>(input, line).collect::<Result<Vec<_>, _>>();
+        assert_eq!(numbers, Ok(vec![8400, 5400]));
+
+        assert_eq!(integers::<u8>("no numbers", "no numbers").count(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn same_as_str(s in "[-+]?[0-9]{0,21}") {
+            prop_assert_eq!(i64::from_digits(s.as_bytes()), s.parse::<i64>().ok());
+            prop_assert_eq!(u32::from_digits(s.as_bytes()), s.parse::<u32>().ok());
+        }
+
+        #[test]
+        fn every_integer(numbers in prop::collection::vec(any::<i64>(), 0..10)) {
+            let line = numbers.iter().map(|n| format!("n={n};")).collect::<String>();
+            let parsed = integers::<i64>(&line, &line).collect::<Result<Vec<_>, _>>();
+            prop_assert_eq!(parsed, Ok(numbers));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use super::{Direction, Direction8};
+
+/// Position on a grid as a row `x` and a column `y`, or a move between two positions
+/// ```
+/// use aoc24::utils::{Direction, Point};
+///
+/// let p = Point::from((2, 3));
+/// assert_eq!(p + Direction::North.into(), Point::new(1, 3));
+/// assert_eq!(p - Point::new(3, 0), Point::new(-1, 3));
+/// assert_eq!(p.bounded_add(Point::new(0, 1), (3, 4)), None);
+/// assert_eq!(<(usize, usize)>::try_from(p * 2), Ok((4, 6)));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// Sum of the points, `None` when it overflows
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add(rhs.x)?,
+            self.y.checked_add(rhs.y)?,
+        ))
+    }
+
+    /// Sum of the points, `None` when it leaves `0..height` and `0..width`
+    pub fn bounded_add(self, rhs: Self, (height, width): (usize, usize)) -> Option<Self> {
+        let sum = self.checked_add(rhs)?;
+        let inside = usize::try_from(sum.x).is_ok_and(|x| x < height)
+            && usize::try_from(sum.y).is_ok_and(|y| y < width);
+
+        inside.then_some(sum)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::new(x as isize, y as isize)
+    }
+}
+
+impl TryFrom<(usize, usize)> for Point {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Self, Self::Error> {
+        Ok(Self::new(x.try_into()?, y.try_into()?))
+    }
+}
+
+impl TryFrom<Point> for (usize, usize) {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(Point { x, y }: Point) -> Result<Self, Self::Error> {
+        Ok((x.try_into()?, y.try_into()?))
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.delta().into()
+    }
+}
+
+impl From<Direction8> for Point {
+    fn from(direction: Direction8) -> Self {
+        direction.delta().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut p = Point::new(1, -2);
+        p += Point::new(3, 3);
+        assert_eq!(p, Point::new(4, 1));
+        p -= Point::new(4, 4);
+        assert_eq!(p, Point::new(0, -3));
+        assert_eq!(-p, Point::new(0, 3));
+
+        assert_eq!(
+            Point::new(isize::MAX, 0).checked_add(Point::new(1, 0)),
+            None
+        );
+        assert_eq!(
+            <(usize, usize)>::try_from(Point::new(0, -1)).map_err(|_| ()),
+            Err(())
+        );
+        assert_eq!(Point::try_from((usize::MAX, 0)).map_err(|_| ()), Err(()));
+        assert_eq!(Point::try_from((3_usize, 4_usize)), Ok(Point::new(3, 4)));
+    }
+
+    #[test]
+    fn bounds() {
+        let corner = Point::new(0, 0);
+        let rectangle = (2, 5);
+        assert_eq!(corner.bounded_add(Point::new(-1, 0), rectangle), None);
+        assert_eq!(corner.bounded_add(Point::new(0, -1), rectangle), None);
+        assert_eq!(corner.bounded_add(Point::new(2, 0), rectangle), None);
+        assert_eq!(
+            corner.bounded_add(Point::new(1, 4), rectangle),
+            Some(Point::new(1, 4))
+        );
+        assert_eq!(corner.bounded_add(Point::new(1, 5), rectangle), None);
+    }
+
+    #[test]
+    fn order() {
+        // Row first, then column
+        let mut points = vec![Point::new(1, 0), Point::new(0, 2), Point::new(0, 1)];
+        points.sort();
+        assert_eq!(
+            points,
+            [Point::new(0, 1), Point::new(0, 2), Point::new(1, 0)]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use rustc_hash::FxHashMap;
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Shortest paths found by [`bfs`], [`dijkstra`] or [`astar`]: the cost of each reached node and
+/// its predecessors on the shortest paths from the starts
+/// ```
+/// use aoc24::utils::dijkstra;
+///
+/// // Numbers reached by adding 1 for a cost of 1, or doubling for a cost of 2
+/// let search = dijkstra([1], |n: u32| [(n + 1, 1), (n * 2, 2)], |n| n == 10);
+/// assert_eq!(search.goal(), Some((10, 6)));
+/// assert_eq!(search.path(10), Some(vec![1, 2, 4, 5, 10]));
+/// assert_eq!(search.cost(4), Some(3));
+/// assert_eq!(search.predecessors(4).collect::<Vec<_>>(), [2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    // Nodes are small and not chosen by an attacker, a fast hash pays off
+    visits: FxHashMap<N, Visit<N, C>>,
+    goal: Option<N>,
+}
+
+/// Cost of a reached node, and the nodes right before it on its shortest paths
+#[derive(Debug, Clone)]
+struct Visit<N, C> {
+    cost: C,
+    // Ties are rare, so the first predecessor is kept apart to avoid allocating
+    predecessor: Option<N>,
+    tied: Vec<N>,
+}
+
+impl<N, C> Visit<N, C> {
+    fn new(cost: C, predecessor: Option<N>) -> Self {
+        Self {
+            cost,
+            predecessor,
+            tied: Vec::new(),
+        }
+    }
+}
+
+impl<N: Copy + Eq + Hash, C: Copy + Ord> Search<N, C> {
+    /// Search with the starts reached for a cost of `zero`, and the starts without duplicates
+    fn new(starts: impl IntoIterator<Item = N>, zero: C) -> (Self, Vec<N>) {
+        let mut visits = FxHashMap::default();
+        let starts = starts
+            .into_iter()
+            .filter(|&start| visits.insert(start, Visit::new(zero, None)).is_none())
+            .collect();
+
+        (Self { visits, goal: None }, starts)
+    }
+
+    /// Record that `next` is reached from `node` for `cost`, returning whether this path is
+    /// shorter than the known ones
+    fn relax(&mut self, node: N, next: N, cost: C) -> bool {
+        match self.visits.entry(next) {
+            Entry::Vacant(entry) => {
+                entry.insert(Visit::new(cost, Some(node)));
+                true
+            }
+            Entry::Occupied(mut entry) => {
+                let visit = entry.get_mut();
+                match cost.cmp(&visit.cost) {
+                    Ordering::Less => {
+                        *visit = Visit::new(cost, Some(node));
+                        true
+                    }
+                    Ordering::Equal => {
+                        visit.tied.push(node);
+                        false
+                    }
+                    Ordering::Greater => false,
+                }
+            }
+        }
+    }
+
+    /// Cost of the shortest path to `node`, `None` when it wasn't reached
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.visits.get(&node).map(|visit| visit.cost)
+    }
+
+    /// Every reached node with the cost of its shortest path, in no particular order
+    pub fn costs(&self) -> impl Iterator<Item = (N, C)> + '_ {
+        self.visits.iter().map(|(&node, visit)| (node, visit.cost))
+    }
+
+    /// Goal where the search stopped, with its cost
+    pub fn goal(&self) -> Option<(N, C)> {
+        let goal = self.goal?;
+        Some((goal, self.visits[&goal].cost))
+    }
+
+    /// Nodes right before `node` on its shortest paths, none for the starts
+    pub fn predecessors(&self, node: N) -> impl Iterator<Item = N> + '_ {
+        self.visits.get(&node).into_iter().flat_map(|visit| {
+            visit
+                .predecessor
+                .into_iter()
+                .chain(visit.tied.iter().copied())
+        })
+    }
+
+    /// One of the shortest paths from a start to `node`, both included
+    pub fn path(&self, node: N) -> Option<Vec<N>> {
+        let mut visit = self.visits.get(&node)?;
+
+        let mut path = vec![node];
+        while let Some(previous) = visit.predecessor {
+            path.push(previous);
+            visit = &self.visits[&previous];
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Every node of the shortest paths from the starts to `ends` with its predecessors on them,
+    /// the DAG of all the shortest paths. Unreached ends are skipped
+    pub fn shortest_paths_dag(&self, ends: impl IntoIterator<Item = N>) -> HashMap<N, Vec<N>> {
+        let mut dag = HashMap::new();
+        let mut stack = ends
+            .into_iter()
+            .filter(|end| self.visits.contains_key(end))
+            .collect::<Vec<_>>();
+
+        while let Some(node) = stack.pop() {
+            if let Entry::Vacant(entry) = dag.entry(node) {
+                let predecessors = self.predecessors(node).collect::<Vec<_>>();
+                stack.extend(&predecessors);
+                entry.insert(predecessors);
+            }
+        }
+
+        dag
+    }
+}
+
+/// Breadth-first search from `starts`, every step costing 1. The search stops once `is_goal`
+/// holds for a node, the whole graph is explored otherwise
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (mut search, starts) = Search::new(starts, 0);
+    let mut queue = starts
+        .into_iter()
+        .map(|start| (start, 0))
+        .collect::<VecDeque<_>>();
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for next in neighbours(node) {
+            if search.relax(node, next, cost + 1) {
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's search from `starts`, `successors` giving the next nodes with the cost to reach
+/// them. Costs must be positive. The search stops once `is_goal` holds for a node, the whole
+/// graph is explored otherwise
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// A* search from `starts`, like [`dijkstra`] but exploring first the nodes that `heuristic`
+/// deems closer to a goal. The heuristic must never overestimate the cost to a goal, nor
+/// decrease by more than the cost of a step. The path to the goal is a shortest one, but
+/// predecessors of nodes tied with the goal can be missing
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(N) -> I,
+    mut heuristic: impl FnMut(N) -> C,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let (mut search, starts) = Search::new(starts, C::default());
+    let mut heap = starts
+        .into_iter()
+        .map(|start| Queued {
+            priority: heuristic(start),
+            cost: C::default(),
+            node: start,
+        })
+        .collect::<BinaryHeap<_>>();
+
+    while let Some(Queued { cost, node, .. }) = heap.pop() {
+        if cost > search.visits[&node].cost {
+            // There is already a better path for this node
+            continue;
+        }
+        if is_goal(node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, step) in successors(node) {
+            let next_cost = cost + step;
+            if search.relax(node, next, next_cost) {
+                heap.push(Queued {
+                    priority: next_cost + heuristic(next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// Node waiting in the heap, compared by priority only
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Comparison is flipped to make the heap pop the smallest priority first
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves of one step on a 5x5 grid, around the walls of the middle column but its bottom
+    fn open_neighbours((i, j): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        [
+            (i.wrapping_sub(1), j),
+            (i + 1, j),
+            (i, j.wrapping_sub(1)),
+            (i, j + 1),
+        ]
+        .into_iter()
+        .filter(|&(i, j)| i < 5 && j < 5 && (j != 2 || i == 4))
+    }
+
+    #[test]
+    fn searches() {
+        let search = bfs([(0, 0)], open_neighbours, |node| node == (0, 4));
+        assert_eq!(search.goal(), Some(((0, 4), 12)));
+        assert_eq!(search.cost((4, 2)), Some(6));
+        let path = search.path((0, 4)).unwrap();
+        assert_eq!(path.len(), 13);
+        assert!(path.windows(2).all(|step| {
+            let ((i1, j1), (i2, j2)) = (step[0], step[1]);
+            i1.abs_diff(i2) + j1.abs_diff(j2) == 1
+        }));
+
+        let unit = |node| open_neighbours(node).map(|next| (next, 1));
+        let search = dijkstra([(0, 0)], unit, |node| node == (0, 4));
+        assert_eq!(search.goal(), Some(((0, 4), 12)));
+
+        let heuristic = |(i, j): (usize, usize)| i.abs_diff(0) + j.abs_diff(4);
+        let search = astar([(0, 0)], unit, heuristic, |node| node == (0, 4));
+        assert_eq!(search.goal(), Some(((0, 4), 12)));
+        assert_eq!(search.path((0, 4)).map(|path| path.len()), Some(13));
+
+        // The whole grid is explored without a goal, but the wall
+        let search = bfs([(0, 0), (0, 4)], open_neighbours, |_| false);
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.costs().count(), 21);
+        assert_eq!(search.cost((4, 2)), Some(6));
+        assert_eq!(search.cost((0, 2)), None);
+        assert_eq!(search.path((0, 2)), None);
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        // Every monotone path from the corner of a 3x3 grid is a shortest one
+        let search = bfs(
+            [(0, 0)],
+            |(i, j): (usize, usize)| {
+                [(i + 1, j), (i, j + 1)]
+                    .into_iter()
+                    .filter(|&(i, j)| i < 3 && j < 3)
+            },
+            |_| false,
+        );
+        assert_eq!(search.predecessors((1, 1)).count(), 2);
+        assert_eq!(search.predecessors((0, 0)).count(), 0);
+        assert_eq!(search.shortest_paths_dag([(2, 2)]).len(), 9);
+        assert_eq!(search.shortest_paths_dag([(0, 2)]).len(), 3);
+
+        // Diagonal moves cost as much as the others, so only the diagonal is the shortest
+        let search = dijkstra(
+            [(0, 0)],
+            |(i, j): (usize, usize)| {
+                [(i + 1, j), (i, j + 1), (i + 1, j + 1)]
+                    .into_iter()
+                    .filter(|&(i, j)| i < 3 && j < 3)
+                    .map(|next| (next, 1))
+            },
+            |_| false,
+        );
+        assert_eq!(search.cost((2, 2)), Some(2));
+        let dag = search.shortest_paths_dag([(2, 2), (1, 0), (7, 7)]);
+        assert_eq!(dag.len(), 4);
+        assert_eq!(dag[&(2, 2)], [(1, 1)]);
+    }
+}